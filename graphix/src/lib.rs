@@ -1,2 +1,72 @@
+//! Literal defaults are checked against the field's type, one that doesn't fit it doesn't
+//! compile:
+//! ```compile_fail
+//! # use graphix::Entity;
+//! # #[derive(Entity)]
+//! # #[graphix(table_name = "counters")]
+//! # struct Counter {
+//! #     #[graphix(primary_key)]
+//! #     id: i64,
+//! #[graphix(default = 100000)]
+//! hits: i16,
+//! # }
+//! ```
+//! ```
+//! # use graphix::Entity;
+//! # #[derive(Entity)]
+//! # #[graphix(table_name = "counters")]
+//! # struct Counter {
+//! #     #[graphix(primary_key)]
+//! #     id: i64,
+//! #[graphix(default = 10000)]
+//! hits: i16,
+//! # }
+//! ```
+//!
+//! Fields marked `immutable` can only be set when a row is created, updating them doesn't
+//! compile:
+//! ```compile_fail
+//! # use graphix::Entity;
+//! # #[derive(Entity)]
+//! # #[graphix(table_name = "users")]
+//! # struct User {
+//! #     #[graphix(primary_key)]
+//! #     id: i64,
+//! #     #[graphix(immutable)]
+//! #     email: String,
+//! #     name: String,
+//! # }
+//! let _ = User::update().set_email("ann@example.com");
+//! ```
+//! ```compile_fail
+//! # use graphix::Entity;
+//! # #[derive(Entity)]
+//! # #[graphix(table_name = "users")]
+//! # struct User {
+//! #     #[graphix(primary_key)]
+//! #     id: i64,
+//! #     #[graphix(immutable)]
+//! #     email: String,
+//! #     name: String,
+//! # }
+//! let _ = graphix::mutation::Update::<User>::new().set(UserColumns::email(), "ann@example.com");
+//! ```
+//! While the mutable ones can be:
+//! ```
+//! # use graphix::Entity;
+//! # #[derive(Entity)]
+//! # #[graphix(table_name = "users")]
+//! # struct User {
+//! #     #[graphix(primary_key)]
+//! #     id: i64,
+//! #     #[graphix(immutable)]
+//! #     email: String,
+//! #     name: String,
+//! # }
+//! let _ = User::create().set_email("ann@example.com");
+//! let _ = User::update().set_name("ann");
+//! let _ = graphix::mutation::Update::<User>::new().set(UserColumns::name(), "ann");
+//! ```
+
 pub use graphix_macros::*;
 pub use graphix_models::*;
@@ -0,0 +1,116 @@
+pub mod lint;
+pub mod source;
+
+use graphix::atlas::AtlasError;
+use graphix::codegen::rust_source;
+use graphix::diff::SchemaDiff;
+use graphix::migrate::{MigrateError, MigrationDir};
+use graphix::schema::Schema;
+use graphix::sql::postgres::ddl;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The snapshot of the schema the latest migration leads to, kept in the migration directory.
+/// Atlas only reads `.sql` files so it doesn't get in the way of `atlas migrate`.
+pub const MIGRATION_SNAPSHOT: &str = "graphix.json";
+
+#[derive(Debug)]
+pub enum CliError {
+    Io(io::Error),
+    Snapshot(PathBuf, serde_json::Error),
+    Atlas(PathBuf, AtlasError),
+    Migrate(MigrateError),
+    /// Building or running the schema helper binary failed.
+    Helper(String),
+}
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Snapshot(path, e) => {
+                write!(f, "invalid snapshot `{}`: {}", path.display(), e)
+            }
+            Self::Atlas(path, e) => write!(f, "invalid Atlas schema `{}`: {}", path.display(), e),
+            Self::Migrate(e) => write!(f, "{}", e),
+            Self::Helper(reason) => write!(f, "schema helper failed: {}", reason),
+        }
+    }
+}
+impl std::error::Error for CliError {}
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl From<MigrateError> for CliError {
+    fn from(e: MigrateError) -> Self {
+        Self::Migrate(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Hcl,
+    Sql,
+    Json,
+}
+
+pub fn read_snapshot(path: &Path) -> Result<Schema, CliError> {
+    let json = fs::read_to_string(path)?;
+    Schema::from_snapshot(&json).map_err(|e| CliError::Snapshot(path.to_path_buf(), e))
+}
+
+pub fn print_schema(schema: &Schema, format: Format) -> String {
+    match format {
+        Format::Hcl => hcl::to_string(&schema.as_atlas_hcl()).expect("HCL bodies always format"),
+        Format::Sql => statements(ddl::schema_statements(schema)),
+        Format::Json => schema.to_snapshot(),
+    }
+}
+
+/// The Rust source of the entities an Atlas HCL file describes.
+pub fn import_hcl(path: &Path) -> Result<String, CliError> {
+    let hcl = fs::read_to_string(path)?;
+    let schema =
+        Schema::from_atlas_hcl(&hcl).map_err(|e| CliError::Atlas(path.to_path_buf(), e))?;
+    Ok(rust_source(&schema))
+}
+
+/// The SQL migrating `old` to `new`.
+pub fn diff_schemas(old: &Schema, new: &Schema) -> String {
+    statements(ddl::diff_statements(&SchemaDiff::between(old, new)))
+}
+
+/// Writes a migration from the schema the directory's snapshot describes to `schema` and
+/// updates the snapshot, `None` when there is nothing to migrate.
+pub fn new_migration(
+    dir: &MigrationDir,
+    name: &str,
+    schema: &Schema,
+) -> Result<Option<PathBuf>, CliError> {
+    let previous = migrated_schema(dir)?;
+    let written = dir.write_now(name, &SchemaDiff::between(&previous, schema))?;
+
+    if written.is_some() {
+        fs::write(dir.path().join(MIGRATION_SNAPSHOT), schema.to_snapshot())?;
+    }
+    Ok(written)
+}
+
+/// The schema the directory's migrations lead to, empty for a new directory.
+pub fn migrated_schema(dir: &MigrationDir) -> Result<Schema, CliError> {
+    let path = dir.path().join(MIGRATION_SNAPSHOT);
+    match path.exists() {
+        true => read_snapshot(&path),
+        false => Ok(Schema::new()),
+    }
+}
+
+fn statements(statements: Vec<String>) -> String {
+    statements
+        .into_iter()
+        .map(|s| s + "\n")
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,103 @@
+use crate::migrated_schema;
+use graphix::descriptor::{EntityDescriptor, IndexPart};
+use graphix::diff::SchemaDiff;
+use graphix::migrate::MigrationDir;
+use graphix::schema::Schema;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub severity: Severity,
+    pub message: String,
+}
+impl Lint {
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+        }
+    }
+}
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Checks the schema, and the migration directory when given: that its files match `atlas.sum`
+/// and that it is up to date with the schema.
+pub fn lint(schema: &Schema, dir: Option<&MigrationDir>) -> Vec<Lint> {
+    let mut lints = Vec::new();
+
+    for table in schema.tables() {
+        let name = format!("{}.{}", table.schema_name, table.table_name);
+
+        if table.primary_key.is_empty() {
+            lints.push(Lint::warning(format!(
+                "table `{}` has no primary key",
+                name
+            )));
+        }
+
+        // postgres doesn't index foreign keys, deletes on the referenced table scan this one
+        for edge in table.foreign_keys() {
+            if !is_indexed(table, &edge.columns) {
+                lints.push(Lint::warning(format!(
+                    "foreign key `{}` on `{}` has no index starting with ({})",
+                    edge.foreign_key_name(&table.table_name),
+                    name,
+                    edge.columns.join(", ")
+                )));
+            }
+        }
+    }
+
+    if let Some(dir) = dir {
+        if let Err(e) = dir.verify() {
+            lints.push(Lint::error(e.to_string()));
+        }
+
+        match migrated_schema(dir) {
+            Ok(migrated) if !SchemaDiff::between(&migrated, schema).is_empty() => {
+                lints.push(Lint::error(
+                    "the schema has changes without a migration, run `graphix migrate new`"
+                        .to_string(),
+                ))
+            }
+            Ok(_) => {}
+            Err(e) => lints.push(Lint::error(e.to_string())),
+        }
+    }
+
+    lints
+}
+
+/// Whether the primary key or an index starts with `columns`.
+fn is_indexed(table: &EntityDescriptor, columns: &[String]) -> bool {
+    table.primary_key.starts_with(columns)
+        || table.indexes.iter().any(|index| {
+            index.predicate.is_none()
+                && index.parts.len() >= columns.len()
+                && index
+                    .parts
+                    .iter()
+                    .zip(columns)
+                    .all(|(part, col)| matches!(part, IndexPart::Column(c) if c == col))
+        })
+}
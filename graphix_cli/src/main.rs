@@ -0,0 +1,128 @@
+use clap::{Args, Parser, Subcommand};
+use graphix::migrate::MigrationDir;
+use graphix_cli::lint::{lint, Severity};
+use graphix_cli::source::SchemaSource;
+use graphix_cli::{
+    diff_schemas, import_hcl, new_migration, print_schema, read_snapshot, CliError, Format,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Inspect graphix schemas and generate migrations.
+#[derive(Parser)]
+#[command(name = "graphix", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(subcommand)]
+    Schema(SchemaCommand),
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Check the schema and the migration directory.
+    Lint {
+        #[command(flatten)]
+        source: Source,
+        #[arg(long, default_value = "migrations")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum SchemaCommand {
+    /// Print the whole schema.
+    Print {
+        #[command(flatten)]
+        source: Source,
+        #[arg(long, value_enum, default_value = "hcl")]
+        format: Format,
+    },
+    /// Print the SQL migrating the schema of one snapshot to another.
+    Diff { old: PathBuf, new: PathBuf },
+    /// Print `#[derive(Entity)]` structs for the tables of an Atlas HCL file.
+    Import { hcl: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Write a migration for the changes since the last one.
+    New {
+        name: String,
+        #[command(flatten)]
+        source: Source,
+        #[arg(long, default_value = "migrations")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct Source {
+    /// Read the schema from a JSON snapshot.
+    #[arg(long, conflicts_with = "krate")]
+    snapshot: Option<PathBuf>,
+    /// Read the schema from the entities of a crate, by default the one in the current directory.
+    #[arg(long = "crate", id = "krate")]
+    krate: Option<PathBuf>,
+}
+impl Source {
+    fn schema_source(&self) -> SchemaSource {
+        match (&self.snapshot, &self.krate) {
+            (Some(path), _) => SchemaSource::Snapshot(path.clone()),
+            (None, Some(dir)) => SchemaSource::Crate(dir.clone()),
+            (None, None) => SchemaSource::Crate(PathBuf::from(".")),
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, CliError> {
+    match cli.command {
+        Command::Schema(SchemaCommand::Print { source, format }) => {
+            print!("{}", print_schema(&source.schema_source().load()?, format));
+        }
+        Command::Schema(SchemaCommand::Diff { old, new }) => {
+            print!(
+                "{}",
+                diff_schemas(&read_snapshot(&old)?, &read_snapshot(&new)?)
+            );
+        }
+        Command::Schema(SchemaCommand::Import { hcl }) => {
+            print!("{}", import_hcl(&hcl)?);
+        }
+        Command::Migrate(MigrateCommand::New { name, source, dir }) => {
+            let schema = source.schema_source().load()?;
+            match new_migration(&MigrationDir::new(dir), &name, &schema)? {
+                Some(path) => println!("wrote {}", path.display()),
+                None => println!("no changes, nothing to migrate"),
+            }
+        }
+        Command::Lint { source, dir } => {
+            let dir = MigrationDir::new(dir);
+            let lints = lint(
+                &source.schema_source().load()?,
+                dir.path().exists().then_some(&dir),
+            );
+
+            for l in &lints {
+                println!("{}", l);
+            }
+            if lints.iter().any(|l| l.severity == Severity::Error) {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Where the CLI gets a schema from: a snapshot file, or the entities of a crate.
+//!
+//! Entities only exist once compiled into a program, so for a crate the CLI generates a small
+//! helper binary linking it, runs it and reads back the snapshot it prints, much like `entc`
+//! drives `ent`.
+
+use crate::{read_snapshot, CliError};
+use graphix::schema::Schema;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone)]
+pub enum SchemaSource {
+    Snapshot(PathBuf),
+    /// The directory of a crate with `graphix` as a dependency.
+    Crate(PathBuf),
+}
+impl SchemaSource {
+    pub fn load(&self) -> Result<Schema, CliError> {
+        match self {
+            Self::Snapshot(path) => read_snapshot(path),
+            Self::Crate(dir) => {
+                let helper = write_helper(dir)?;
+                run_helper(dir, &helper)
+            }
+        }
+    }
+}
+
+/// Writes the helper crate to `target/graphix-helper` and returns its manifest path.
+pub fn write_helper(dir: &Path) -> Result<PathBuf, CliError> {
+    let dir = dir.canonicalize()?;
+    let manifest: toml::Table = fs::read_to_string(dir.join("Cargo.toml"))?
+        .parse()
+        .map_err(|e| CliError::Helper(format!("invalid Cargo.toml: {}", e)))?;
+
+    let name = manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| CliError::Helper("Cargo.toml has no package name".to_string()))?;
+    let graphix = manifest
+        .get("dependencies")
+        .and_then(|d| d.get("graphix"))
+        .ok_or_else(|| CliError::Helper(format!("`{}` doesn't depend on graphix", name)))?;
+    let lib_name = lib_name(&dir, &manifest, name).ok_or_else(|| {
+        CliError::Helper(format!(
+            "`{}` has no library target, the helper can only link entities declared in a lib",
+            name
+        ))
+    })?;
+
+    let helper_dir = dir.join("target").join("graphix-helper");
+    fs::create_dir_all(helper_dir.join("src"))?;
+
+    // resolve dependencies to the versions the crate (or its workspace) already uses
+    if let Some(lock) = dir
+        .ancestors()
+        .map(|d| d.join("Cargo.lock"))
+        .find(|l| l.exists())
+    {
+        fs::copy(lock, helper_dir.join("Cargo.lock"))?;
+    }
+
+    let manifest_path = helper_dir.join("Cargo.toml");
+    fs::write(
+        &manifest_path,
+        helper_manifest(&dir, name, graphix).to_string(),
+    )?;
+    fs::write(
+        helper_dir.join("src").join("main.rs"),
+        format!(
+            "extern crate {} as _;\n\n\
+             fn main() {{\n    \
+                 print!(\"{{}}\", graphix::schema::Schema::registered().to_snapshot());\n\
+             }}\n",
+            lib_name
+        ),
+    )?;
+
+    Ok(manifest_path)
+}
+
+/// The name the crate's library is linked as, `None` if it has no `[lib]` table nor a
+/// `src/lib.rs`.
+fn lib_name(dir: &Path, manifest: &toml::Table, name: &str) -> Option<String> {
+    let lib = manifest.get("lib");
+    if lib.is_none() && !dir.join("src").join("lib.rs").exists() {
+        return None;
+    }
+
+    let name = lib
+        .and_then(|l| l.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or(name);
+    Some(name.replace('-', "_"))
+}
+
+/// The helper depends on the crate and on the same graphix the crate uses, with snapshots
+/// enabled. Cargo unifies the two so the helper sees the crate's registrations.
+fn helper_manifest(dir: &Path, name: &str, graphix: &toml::Value) -> toml::Table {
+    let mut graphix = match graphix {
+        toml::Value::Table(t) => t.clone(),
+        version => toml::Table::from_iter([("version".to_string(), version.clone())]),
+    };
+    if let Some(path) = graphix.get("path").and_then(|p| p.as_str()) {
+        let path = dir.join(path).to_string_lossy().to_string();
+        graphix.insert("path".to_string(), path.into());
+    }
+    let mut features = graphix
+        .get("features")
+        .and_then(|f| f.as_array())
+        .cloned()
+        .unwrap_or_default();
+    features.push("serde".into());
+    graphix.insert("features".to_string(), features.into());
+
+    let mut user_crate = toml::Table::new();
+    user_crate.insert("path".to_string(), dir.to_string_lossy().to_string().into());
+
+    let mut dependencies = toml::Table::new();
+    dependencies.insert(name.to_string(), user_crate.into());
+    dependencies.insert("graphix".to_string(), graphix.into());
+
+    let mut package = toml::Table::new();
+    package.insert("name".to_string(), "graphix-helper".into());
+    package.insert("version".to_string(), "0.0.0".into());
+    package.insert("edition".to_string(), "2021".into());
+    package.insert("publish".to_string(), false.into());
+
+    let mut manifest = toml::Table::new();
+    manifest.insert("package".to_string(), package.into());
+    manifest.insert("dependencies".to_string(), dependencies.into());
+    // keeps the helper out of any workspace the crate is part of
+    manifest.insert("workspace".to_string(), toml::Table::new().into());
+    manifest
+}
+
+fn run_helper(dir: &Path, manifest_path: &Path) -> Result<Schema, CliError> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .arg("run")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--target-dir")
+        .arg(dir.join("target"))
+        .output()?;
+
+    if !output.status.success() {
+        return Err(CliError::Helper(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    let json = String::from_utf8_lossy(&output.stdout);
+    Schema::from_snapshot(&json).map_err(|e| CliError::Snapshot(manifest_path.to_path_buf(), e))
+}
@@ -0,0 +1,148 @@
+use graphix::entity::Entity;
+use graphix::migrate::MigrationDir;
+use graphix::schema::Schema;
+use graphix::Entity;
+use graphix_cli::lint::{lint, Severity};
+use graphix_cli::source::SchemaSource;
+use graphix_cli::{
+    diff_schemas, migrated_schema, new_migration, print_schema, read_snapshot, CliError, Format,
+    MIGRATION_SNAPSHOT,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Entity)]
+#[graphix(table_name = "groups")]
+#[allow(dead_code)]
+struct Group {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "users", edge(to = Group, field = "group_id"))]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    group_id: i64,
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graphix_cli_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn groups() -> Schema {
+    [Group::descriptor()].into_iter().collect()
+}
+
+fn groups_and_users() -> Schema {
+    [Group::descriptor(), User::descriptor()]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_print_schema() {
+    let schema = groups();
+
+    let json = print_schema(&schema, Format::Json);
+    assert_eq!(Schema::from_snapshot(&json).unwrap(), schema);
+
+    let sql = print_schema(&schema, Format::Sql);
+    assert!(sql.starts_with("CREATE SCHEMA IF NOT EXISTS \"public\";\n\nCREATE TABLE"));
+
+    let hcl = print_schema(&schema, Format::Hcl);
+    assert!(hcl.contains("table \"groups\" {"));
+}
+
+#[test]
+fn test_diff_snapshots() {
+    let dir = temp_dir("diff");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("old.json"), groups().to_snapshot()).unwrap();
+
+    let old = read_snapshot(&dir.join("old.json")).unwrap();
+    let sql = diff_schemas(&old, &groups_and_users());
+    assert!(sql.starts_with("CREATE TABLE \"public\".\"users\""));
+    assert!(sql.contains("ADD CONSTRAINT"));
+
+    assert!(diff_schemas(&old, &old).is_empty());
+    assert!(read_snapshot(&dir.join("missing.json")).is_err());
+}
+
+#[test]
+fn test_new_migration() {
+    let dir = MigrationDir::new(temp_dir("migrate"));
+
+    let first = new_migration(&dir, "init", &groups()).unwrap().unwrap();
+    assert!(first.to_string_lossy().ends_with("_init.sql"));
+    assert_eq!(migrated_schema(&dir).unwrap(), groups());
+
+    // nothing changed since the snapshot
+    assert_eq!(new_migration(&dir, "again", &groups()).unwrap(), None);
+
+    // only the new table is migrated
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    let second = new_migration(&dir, "users", &groups_and_users())
+        .unwrap()
+        .unwrap();
+    let sql = fs::read_to_string(second).unwrap();
+    assert!(!sql.contains("CREATE TABLE \"public\".\"groups\""));
+    assert!(sql.contains("CREATE TABLE \"public\".\"users\""));
+
+    assert!(dir.path().join(MIGRATION_SNAPSHOT).exists());
+    dir.verify().unwrap();
+}
+
+#[test]
+fn test_lint() {
+    let lints = lint(&groups_and_users(), None);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].severity, Severity::Warning);
+    assert!(lints[0].to_string().starts_with("warning: foreign key"));
+
+    let dir = MigrationDir::new(temp_dir("lint"));
+    new_migration(&dir, "init", &groups()).unwrap();
+    assert!(lint(&groups(), Some(&dir)).is_empty());
+
+    // users has no migration yet
+    let lints = lint(&groups_and_users(), Some(&dir));
+    assert!(lints
+        .iter()
+        .any(|l| l.severity == Severity::Error && l.message.contains("without a migration")));
+
+    // edited by hand
+    let file = dir.files().unwrap().remove(0).0;
+    fs::write(dir.path().join(file), "DROP TABLE groups;\n").unwrap();
+    let lints = lint(&groups(), Some(&dir));
+    assert!(lints
+        .iter()
+        .any(|l| l.severity == Severity::Error && l.message.contains("checksum mismatch")));
+}
+
+#[test]
+fn test_crate_source() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    // builds and runs the helper against a crate whose lib is named differently
+    let schema = SchemaSource::Crate(manifest_dir.join("tests/fixtures/blog"))
+        .load()
+        .unwrap();
+    let posts = schema.table("schema.public", "posts").unwrap();
+    assert_eq!(posts.name, "Post");
+    assert_eq!(schema.tables().count(), 1);
+
+    // graphix_test only has integration tests, there's nothing for the helper to link
+    let err = SchemaSource::Crate(manifest_dir.join("../graphix_test"))
+        .load()
+        .unwrap_err();
+    assert!(
+        matches!(&err, CliError::Helper(reason) if reason.contains("no library target")),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,9 @@
+use graphix::Entity;
+
+#[derive(Entity)]
+#[graphix(table_name = "posts")]
+pub struct Post {
+    #[graphix(primary_key)]
+    pub id: i64,
+    pub title: String,
+}
@@ -13,6 +13,25 @@ pub fn entity(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     toks.into()
 }
 
+#[proc_macro_derive(EdgeSchema, attributes(graphix))]
+pub fn edge_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast: DeriveInput = syn::parse_macro_input!(input as DeriveInput);
+
+    let toks =
+        macros::edge_schema::edge_schema_inner(&ast).unwrap_or_else(|err| err.to_compile_error());
+    debug_print_generated(&ast, &toks);
+    toks.into()
+}
+
+#[proc_macro_derive(SqlEnum, attributes(graphix))]
+pub fn sql_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast: DeriveInput = syn::parse_macro_input!(input as DeriveInput);
+
+    let toks = macros::sql_enum::sql_enum_inner(&ast).unwrap_or_else(|err| err.to_compile_error());
+    debug_print_generated(&ast, &toks);
+    toks.into()
+}
+
 fn debug_print_generated(ast: &DeriveInput, toks: &TokenStream) {
     let debug = env::var("GRAPHIX_DEBUG");
     if let Ok(s) = debug {
@@ -0,0 +1,360 @@
+use crate::macros::entity::{str_array, str_value};
+use crate::macros::to_snake_case;
+use graphix_models::descriptor::ReferenceAction;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{punctuated::Punctuated, spanned::Spanned, Expr, Meta, Token};
+
+/// `edge(to = Group, field = "group_id", ...)` or its inverse `edge(from = User, field = "group_id", ...)`.
+/// Many-to-many edges are declared with `m2m` and have no fields of their own, e.g.
+/// `edge(to = Group, m2m, through = "memberships", edge_schema = Membership)`.
+pub struct EdgeAttribute {
+    pub span: Span,
+    pub target: syn::Path,
+    pub inverse: bool,
+    pub name: Option<String>,
+    pub fields: Vec<syn::LitStr>,
+    pub unique: bool,
+    pub on_delete: Option<ReferenceAction>,
+    pub on_update: Option<ReferenceAction>,
+    pub m2m: bool,
+    pub through: Option<String>,
+    pub edge_schema: Option<syn::Path>,
+}
+impl EdgeAttribute {
+    pub fn from_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut target: Option<(syn::Path, bool)> = None;
+        let mut name = None;
+        let mut fields = Vec::new();
+        let mut unique = false;
+        let mut on_delete = None;
+        let mut on_update = None;
+        let mut m2m = false;
+        let mut through = None;
+        let mut edge_schema = None;
+
+        let args: Punctuated<Meta, Token![,]> =
+            list.parse_args_with(Punctuated::parse_terminated)?;
+
+        for arg in &args {
+            match arg {
+                Meta::Path(path) if path.is_ident("unique") => unique = true,
+                Meta::Path(path) if path.is_ident("m2m") => m2m = true,
+
+                Meta::NameValue(arg) => {
+                    let attr_name = match arg.path.get_ident() {
+                        Some(i) => i.to_string(),
+                        None => return Err(syn::Error::new_spanned(arg, "invalid formatting")),
+                    };
+
+                    match attr_name.as_str() {
+                        "to" | "from" => {
+                            if target.is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    arg,
+                                    "edge must have exactly one of `to` or `from`",
+                                ));
+                            }
+
+                            let Expr::Path(path) = &arg.value else {
+                                return Err(syn::Error::new_spanned(
+                                    &arg.value,
+                                    "expected an entity type",
+                                ));
+                            };
+                            target = Some((path.path.clone(), attr_name == "from"));
+                        }
+                        "name" => name = Some(str_value(&arg.value)?),
+                        "field" => {
+                            fields =
+                                vec![syn::LitStr::new(&str_value(&arg.value)?, arg.value.span())]
+                        }
+                        "fields" => fields = str_array(&arg.value)?,
+                        "on_delete" => on_delete = Some(reference_action(&arg.value)?),
+                        "on_update" => on_update = Some(reference_action(&arg.value)?),
+                        "through" => through = Some(str_value(&arg.value)?),
+                        "edge_schema" => {
+                            let Expr::Path(path) = &arg.value else {
+                                return Err(syn::Error::new_spanned(
+                                    &arg.value,
+                                    "expected an edge schema type",
+                                ));
+                            };
+                            edge_schema = Some(path.path.clone());
+                        }
+
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                arg,
+                                format!("unknown edge attribute `{}`", attr_name),
+                            ))
+                        }
+                    }
+                }
+
+                _ => return Err(syn::Error::new_spanned(arg, "unknown edge attribute")),
+            }
+        }
+
+        let Some((target, inverse)) = target else {
+            return Err(syn::Error::new_spanned(
+                list,
+                "edge must have one of `to` or `from`",
+            ));
+        };
+
+        if m2m {
+            if !fields.is_empty() || unique || on_delete.is_some() || on_update.is_some() {
+                return Err(syn::Error::new_spanned(
+                    list,
+                    "`m2m` edges keep their foreign keys in the join table, \
+                     `field`, `fields`, `unique`, `on_delete` and `on_update` can't be used",
+                ));
+            }
+            if inverse && edge_schema.is_some() {
+                return Err(syn::Error::new_spanned(
+                    list,
+                    "`edge_schema` belongs on the edge that owns the join table",
+                ));
+            }
+            if target.is_ident("Self") && name.is_none() {
+                return Err(syn::Error::new_spanned(
+                    list,
+                    "self referencing `m2m` edges must be given a `name`",
+                ));
+            }
+        } else if through.is_some() || edge_schema.is_some() {
+            return Err(syn::Error::new_spanned(
+                list,
+                "`through` and `edge_schema` can only be used on `m2m` edges",
+            ));
+        } else if fields.is_empty() {
+            return Err(syn::Error::new_spanned(
+                list,
+                "edge must name its foreign key with `field` or `fields`",
+            ));
+        }
+
+        if inverse && (on_delete.is_some() || on_update.is_some()) {
+            return Err(syn::Error::new_spanned(
+                list,
+                "`on_delete` and `on_update` belong on the edge that owns the foreign key",
+            ));
+        }
+
+        Ok(Self {
+            span: list.span(),
+            target,
+            inverse,
+            name,
+            fields,
+            unique,
+            on_delete,
+            on_update,
+            m2m,
+            through,
+            edge_schema,
+        })
+    }
+
+    /// The target type, with `Self` replaced by the deriving struct so the tokens can be used
+    /// outside of its impl blocks.
+    pub fn target_type(&self, entity: &syn::Ident) -> TokenStream {
+        if self.target.is_ident("Self") {
+            quote! { #entity }
+        } else {
+            let target = &self.target;
+            quote! { #target }
+        }
+    }
+
+    /// The name of the target entity, with `Self` replaced by the deriving struct.
+    fn target_name(&self, entity: &syn::Ident) -> String {
+        match self.target.segments.last() {
+            Some(s) if s.ident == "Self" => entity.to_string(),
+            Some(s) => s.ident.to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Prefix of the join table columns referencing the target of a many-to-many edge. A self
+    /// referencing edge would otherwise use the same prefix on both sides, so it uses its name.
+    fn target_prefix(&self, entity: &syn::Ident) -> String {
+        match &self.name {
+            Some(name) if self.target.is_ident("Self") && !self.inverse => to_snake_case(name),
+            _ => to_snake_case(&self.target_name(entity)),
+        }
+    }
+
+    /// The join table of a many-to-many edge, defaults to `{owner table}_{target table}`.
+    fn through_tokens(&self, table_name: &str, target_table: &TokenStream) -> TokenStream {
+        match (&self.through, self.inverse) {
+            (Some(through), _) => quote! { #through.to_string() },
+            (None, false) => quote! { format!("{}_{}", #table_name, #target_table) },
+            (None, true) => quote! { format!("{}_{}", #target_table, #table_name) },
+        }
+    }
+
+    /// Builds the `EdgeDescriptor` expression. `columns` are the resolved foreign key columns and
+    /// `primary_key` the primary key of the deriving entity.
+    pub fn descriptor_tokens(
+        &self,
+        entity: &syn::Ident,
+        table_name: &str,
+        columns: &[String],
+        primary_key: &[String],
+    ) -> TokenStream {
+        let target = self.target_type(entity);
+        let table = quote! { <#target as graphix::entity::EntityTable> };
+
+        let name = self.name.clone().unwrap_or_else(|| {
+            let target_name = to_snake_case(&self.target_name(entity));
+            match (self.inverse || self.m2m, self.unique) {
+                (true, false) => format!("{}s", target_name),
+                _ => target_name,
+            }
+        });
+
+        let cardinality = match (self.m2m, self.inverse, self.unique) {
+            (true, _, _) => quote! { graphix::descriptor::EdgeCardinality::M2M },
+            (_, _, true) => quote! { graphix::descriptor::EdgeCardinality::O2O },
+            (_, false, false) => quote! { graphix::descriptor::EdgeCardinality::M2O },
+            (_, true, false) => quote! { graphix::descriptor::EdgeCardinality::O2M },
+        };
+
+        let (columns, ref_columns, through) = if self.m2m {
+            let prefix = self.target_prefix(entity);
+            (
+                quote! {
+                    #table::PRIMARY_KEY.iter().map(|c| format!("{}_{}", #prefix, c)).collect()
+                },
+                quote! { #table::PRIMARY_KEY.iter().map(|c| c.to_string()).collect() },
+                {
+                    let through = self.through_tokens(table_name, &quote! { #table::TABLE_NAME });
+                    quote! { Some(#through) }
+                },
+            )
+        } else if self.inverse {
+            (
+                quote! { vec![#(#columns.to_string()),*] },
+                quote! { vec![#(#primary_key.to_string()),*] },
+                quote! { None },
+            )
+        } else {
+            (
+                quote! { vec![#(#columns.to_string()),*] },
+                quote! { #table::PRIMARY_KEY.iter().map(|c| c.to_string()).collect() },
+                quote! { None },
+            )
+        };
+
+        let inverse = self.inverse;
+        let on_delete = match &self.on_delete {
+            Some(a) => quote! { Some(#a) },
+            None => quote! { None },
+        };
+        let on_update = match &self.on_update {
+            Some(a) => quote! { Some(#a) },
+            None => quote! { None },
+        };
+
+        quote! {
+            graphix::descriptor::EdgeDescriptor {
+                name: #name.to_string(),
+                target: #table::NAME.to_string(),
+                target_table: #table::TABLE_NAME.to_string(),
+                target_schema: #table::SCHEMA_NAME.to_string(),
+                cardinality: #cardinality,
+                inverse: #inverse,
+                columns: #columns,
+                ref_columns: #ref_columns,
+                on_delete: #on_delete,
+                on_update: #on_update,
+                through: #through,
+            },
+        }
+    }
+
+    /// Builds the join table `EntityDescriptor` of a many-to-many edge, `None` for other edges
+    /// and for the inverse side which doesn't own the join table.
+    pub fn join_table_tokens(
+        &self,
+        entity: &syn::Ident,
+        table_name: &str,
+        schema_name: &str,
+    ) -> Option<TokenStream> {
+        if !self.m2m || self.inverse {
+            return None;
+        }
+
+        let target = self.target_type(entity);
+        let table = quote! { <#target as graphix::entity::EntityTable> };
+        let own_table = quote! { <#entity as graphix::entity::EntityTable> };
+        let through = self.through_tokens(table_name, &quote! { #table::TABLE_NAME });
+        let own_prefix = to_snake_case(&entity.to_string());
+        let target_prefix = self.target_prefix(entity);
+        let edge_schema = match &self.edge_schema {
+            Some(path) => quote! { <#path as graphix::entity::EdgeSchema> },
+            None => quote! { <() as graphix::entity::EdgeSchema> },
+        };
+
+        Some(quote! {
+            graphix::descriptor::EntityDescriptor::join_table(
+                &#through,
+                #schema_name,
+                graphix::descriptor::JoinSide {
+                    entity: #own_table::NAME.to_string(),
+                    table_name: #own_table::TABLE_NAME.to_string(),
+                    schema_name: #own_table::SCHEMA_NAME.to_string(),
+                    column_prefix: #own_prefix.to_string(),
+                    primary_key: #own_table::primary_key_fields(),
+                },
+                graphix::descriptor::JoinSide {
+                    entity: #table::NAME.to_string(),
+                    table_name: #table::TABLE_NAME.to_string(),
+                    schema_name: #table::SCHEMA_NAME.to_string(),
+                    column_prefix: #target_prefix.to_string(),
+                    primary_key: #table::primary_key_fields(),
+                },
+                #edge_schema::fields(),
+                #edge_schema::enums(),
+            ),
+        })
+    }
+
+    /// A compile time check that the foreign key has as many columns as the key it references.
+    pub fn check_tokens(&self, entity: &syn::Ident) -> TokenStream {
+        let target = self.target_type(entity);
+        let count = self.fields.len();
+
+        if self.m2m {
+            quote_spanned! { self.span=>
+                const _: () = assert!(
+                    !<#target as graphix::entity::EntityTable>::PRIMARY_KEY.is_empty(),
+                    "`m2m` edges can only target entities with a primary key",
+                );
+            }
+        } else if self.inverse {
+            // the foreign key lives on the target, it references our own primary key
+            quote_spanned! { self.span=>
+                const _: () = assert!(
+                    <#entity as graphix::entity::EntityTable>::PRIMARY_KEY.len() == #count,
+                    "inverse edge must name as many fields as this entity's primary key has columns",
+                );
+            }
+        } else {
+            quote_spanned! { self.span=>
+                const _: () = assert!(
+                    <#target as graphix::entity::EntityTable>::PRIMARY_KEY.len() == #count,
+                    "edge must name as many fields as the target's primary key has columns",
+                );
+            }
+        }
+    }
+}
+
+fn reference_action(value: &Expr) -> syn::Result<ReferenceAction> {
+    str_value(value)?
+        .parse()
+        .map_err(|e: String| syn::Error::new_spanned(value, e))
+}
@@ -0,0 +1,68 @@
+use crate::macros::entity::{parse_fields, ParsedFields};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+pub fn edge_schema_inner(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+
+    let fields = match &ast.data {
+        syn::Data::Struct(s) => match &s.fields {
+            syn::Fields::Named(f) => &f.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "only structs with named fields can derive `EdgeSchema`",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "only structs can derive `EdgeSchema`",
+            ))
+        }
+    };
+
+    let ParsedFields {
+        field_desc_tokens,
+        checks,
+        enum_desc_tokens,
+        field_primary_key,
+        unique_columns,
+        ..
+    } = parse_fields(fields)?;
+
+    // the join table's key is made up of its foreign keys, a unique edge field would limit every
+    // row to a single value
+    if !field_primary_key.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "edge schema fields cannot be part of the primary key",
+        ));
+    }
+    if !unique_columns.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "edge schema fields cannot be `unique`",
+        ));
+    }
+
+    Ok(quote! {
+        #(#checks)*
+
+        impl graphix::entity::EdgeSchema for #name {
+            fn fields() -> Vec<graphix::descriptor::EntityFieldDescriptor> {
+                vec![
+                    #(#field_desc_tokens)*
+                ]
+            }
+
+            fn enums() -> Vec<graphix::descriptor::EnumDescriptor> {
+                let mut enums = Vec::new();
+                #(#enum_desc_tokens)*
+                enums
+            }
+        }
+    })
+}
@@ -1,19 +1,58 @@
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
-use syn::{punctuated::Punctuated, DeriveInput, Expr, Lit, Meta, MetaNameValue, Token};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, DeriveInput, Expr, GenericArgument, Lit, Meta,
+    PathArguments, Token, Type,
+};
+use crate::macros::edge::EdgeAttribute;
+use crate::macros::query::query_tokens;
+use graphix_models::descriptor::{ColumnDefault, IndexMethod, ReferenceAction};
 use graphix_models::sql::postgres::ColumnType;
 
 enum StructAttribute {
     TableName(String),
     SchemaName(String),
+    PrimaryKey(Vec<syn::LitStr>),
+    NoPrimaryKey,
+    Index(IndexAttribute),
+    Edge(EdgeAttribute),
 }
 impl StructAttribute {
-    pub fn from_meta(arg: &MetaNameValue) -> syn::Result<Self> {
+    pub fn from_meta(meta: &Meta) -> syn::Result<Self> {
+        let arg = match meta {
+            Meta::Path(path) => {
+                return match path.require_ident()?.to_string().as_str() {
+                    "no_primary_key" => Ok(Self::NoPrimaryKey),
+
+                    _ => Err(syn::Error::new_spanned(
+                        path,
+                        format!("unknown attribute `{}`", path.require_ident()?),
+                    )),
+                }
+            }
+
+            Meta::NameValue(arg) => arg,
+
+            Meta::List(list) if list.path.is_ident("index") => {
+                return Ok(Self::Index(IndexAttribute::from_list(list)?))
+            }
+
+            Meta::List(list) if list.path.is_ident("edge") => {
+                return Ok(Self::Edge(EdgeAttribute::from_list(list)?))
+            }
+
+            _ => return Err(syn::Error::new_spanned(meta, "invalid attribute")),
+        };
+
         let name = match arg.path.get_ident() {
             Some(i) => i.to_string(),
             None => return Err(syn::Error::new_spanned(arg, "invalid formatting")),
         };
 
+        if name == "primary_key" {
+            return Ok(Self::PrimaryKey(str_array(&arg.value)?));
+        }
+
         let value = match &arg.value {
             Expr::Lit(s) => match &s.lit {
                 Lit::Str(s) => s.value(),
@@ -47,11 +86,172 @@ impl StructAttribute {
     }
 }
 
+/// One part of an index, a column name or an `expr("...")`.
+enum IndexPartAttribute {
+    Column(syn::LitStr),
+    Expr(String),
+}
+
+/// `index(columns = [...], unique, method = "...", predicate = "...", name = "...")`, or with
+/// `parts = ["a", expr("..."), ...]` instead of `columns` for indexes on expressions.
+struct IndexAttribute {
+    span: proc_macro2::Span,
+    name: Option<String>,
+    parts: Vec<IndexPartAttribute>,
+    unique: bool,
+    method: Option<IndexMethod>,
+    predicate: Option<String>,
+}
+impl IndexAttribute {
+    pub fn from_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut index = Self {
+            span: list.span(),
+            name: None,
+            parts: Vec::new(),
+            unique: false,
+            method: None,
+            predicate: None,
+        };
+
+        let args: Punctuated<Meta, Token![,]> =
+            list.parse_args_with(Punctuated::parse_terminated)?;
+
+        for arg in &args {
+            match arg {
+                Meta::Path(path) if path.is_ident("unique") => index.unique = true,
+
+                Meta::NameValue(arg) => {
+                    let name = match arg.path.get_ident() {
+                        Some(i) => i.to_string(),
+                        None => return Err(syn::Error::new_spanned(arg, "invalid formatting")),
+                    };
+
+                    match name.as_str() {
+                        "columns" | "parts" if !index.parts.is_empty() => {
+                            return Err(syn::Error::new_spanned(
+                                arg,
+                                "index can only have one of `columns` and `parts`",
+                            ))
+                        }
+                        "columns" => {
+                            index.parts = str_array(&arg.value)?
+                                .into_iter()
+                                .map(IndexPartAttribute::Column)
+                                .collect()
+                        }
+                        "parts" => index.parts = index_parts(&arg.value)?,
+                        "name" => index.name = Some(str_value(&arg.value)?),
+                        "predicate" => index.predicate = Some(str_value(&arg.value)?),
+                        "method" => {
+                            index.method = Some(
+                                str_value(&arg.value)?
+                                    .parse()
+                                    .map_err(|e: String| syn::Error::new_spanned(&arg.value, e))?,
+                            )
+                        }
+
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                arg,
+                                format!("unknown index attribute `{}`", name),
+                            ))
+                        }
+                    }
+                }
+
+                _ => return Err(syn::Error::new_spanned(arg, "unknown index attribute")),
+            }
+        }
+
+        if index.parts.is_empty() {
+            return Err(syn::Error::new_spanned(
+                list,
+                "index must have `columns` or `parts`",
+            ));
+        }
+
+        Ok(index)
+    }
+}
+
+/// Parses the parts of an index in order, such as `[expr("lower(name)"), "id"]`.
+fn index_parts(value: &Expr) -> syn::Result<Vec<IndexPartAttribute>> {
+    let Expr::Array(arr) = value else {
+        return Err(syn::Error::new_spanned(
+            value,
+            "expected an array of columns and expressions, e.g. `[\"a\", expr(\"lower(b)\")]`",
+        ));
+    };
+
+    if arr.elems.is_empty() {
+        return Err(syn::Error::new_spanned(arr, "value cannot be empty"));
+    }
+
+    arr.elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Ok(IndexPartAttribute::Column(s.clone())),
+            Expr::Call(call)
+                if matches!(&*call.func, Expr::Path(p) if p.path.is_ident("expr"))
+                    && call.args.len() == 1 =>
+            {
+                Ok(IndexPartAttribute::Expr(str_value(&call.args[0])?))
+            }
+            _ => Err(syn::Error::new_spanned(
+                elem,
+                "expected a column name or `expr(\"...\")`",
+            )),
+        })
+        .collect()
+}
+
+/// Parses a non-empty string literal.
+pub(crate) fn str_value(value: &Expr) -> syn::Result<String> {
+    match value {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) if !s.value().is_empty() => Ok(s.value()),
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Err(syn::Error::new_spanned(s, "value cannot be empty")),
+        _ => Err(syn::Error::new_spanned(value, "invalid formatting")),
+    }
+}
+
+/// Parses a non-empty array of string literals such as `["a", "b"]`.
+pub(crate) fn str_array(value: &Expr) -> syn::Result<Vec<syn::LitStr>> {
+    let Expr::Array(arr) = value else {
+        return Err(syn::Error::new_spanned(
+            value,
+            "expected an array of strings, e.g. `[\"a\", \"b\"]`",
+        ));
+    };
+
+    if arr.elems.is_empty() {
+        return Err(syn::Error::new_spanned(arr, "value cannot be empty"));
+    }
+
+    arr.elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Ok(s.clone()),
+            _ => Err(syn::Error::new_spanned(elem, "expected a string")),
+        })
+        .collect()
+}
+
 enum FieldAttribute {
     Unique,
     Immutable,
     Nullable,
+    PrimaryKey,
     ColumnName(String),
+    SqlType(ColumnType),
+    Default(ColumnDefault),
 }
 impl FieldAttribute {
     pub fn from_meta(meta: &Meta) -> syn::Result<Self> {
@@ -63,6 +263,8 @@ impl FieldAttribute {
 
                 "unique" => Ok(Self::Unique),
 
+                "primary_key" => Ok(Self::PrimaryKey),
+
                 _ => Err(syn::Error::new_spanned(
                     path,
                     format!("unknown attribute {}", path.require_ident()?),
@@ -75,6 +277,10 @@ impl FieldAttribute {
                     None => return Err(syn::Error::new_spanned(arg, "invalid formatting")),
                 };
 
+                if name == "default" {
+                    return default_literal(&arg.value).map(Self::Default);
+                }
+
                 let value = match &arg.value {
                     Expr::Lit(s) => match &s.lit {
                         Lit::Str(s) => s.value(),
@@ -95,6 +301,22 @@ impl FieldAttribute {
                         Ok(Self::ColumnName(value))
                     }
 
+                    "default_expr" => {
+                        if value.is_empty() {
+                            return Err(syn::Error::new_spanned(
+                                &arg.value,
+                                "default_expr attribute must have a value",
+                            ));
+                        }
+
+                        Ok(Self::Default(ColumnDefault::Expr(value)))
+                    }
+
+                    "sql_type" => value
+                        .parse::<ColumnType>()
+                        .map(Self::SqlType)
+                        .map_err(|e| syn::Error::new_spanned(&arg.value, e)),
+
                     _ => Err(syn::Error::new_spanned(
                         arg,
                         format!("unknown attribute {}", name),
@@ -107,6 +329,286 @@ impl FieldAttribute {
     }
 }
 
+/// Parses the literal of `#[graphix(default = ...)]`.
+fn default_literal(value: &Expr) -> syn::Result<ColumnDefault> {
+    let (lit, negative) = match value {
+        Expr::Lit(lit) => (&lit.lit, false),
+        Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(lit) => (&lit.lit, true),
+            _ => return Err(syn::Error::new_spanned(value, "default must be a literal")),
+        },
+        _ => return Err(syn::Error::new_spanned(value, "default must be a literal")),
+    };
+    let sign = if negative { "-" } else { "" };
+
+    match lit {
+        Lit::Bool(b) if !negative => Ok(ColumnDefault::Bool(b.value)),
+        Lit::Str(s) if !negative => Ok(ColumnDefault::Str(s.value())),
+        Lit::Int(i) => format!("{}{}", sign, i.base10_digits())
+            .parse()
+            .map(ColumnDefault::Int)
+            .map_err(|_| syn::Error::new_spanned(value, "integer default does not fit in an i64")),
+        Lit::Float(f) => Ok(ColumnDefault::Float(format!("{}{}", sign, f.base10_digits()))),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            "default must be a bool, integer, float or string literal",
+        )),
+    }
+}
+
+/// Returns the `T` in `Option<T>`, or `None` if `ty` is not an `Option`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    if tp.qself.is_some() {
+        return None;
+    }
+
+    let segment = tp.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The parsed fields of a struct deriving `Entity` or `EdgeSchema`.
+pub(crate) struct ParsedFields {
+    /// `EntityFieldDescriptor` expressions, each followed by a comma.
+    pub field_desc_tokens: Vec<TokenStream>,
+    /// Compile time checks to emit alongside the impls.
+    pub checks: Vec<TokenStream>,
+    /// Statements pushing the enums the fields depend on into a `Vec` called `enums`.
+    pub enum_desc_tokens: Vec<TokenStream>,
+    /// (field name, column name, declared as `Option<T>`) of every field, in the same order as
+    /// `field_desc_tokens`.
+    pub columns: Vec<(String, String, bool)>,
+    /// The `sql_type` of every field that declares one, in the same order as `columns`.
+    pub sql_types: Vec<Option<ColumnType>>,
+    pub field_primary_key: Vec<String>,
+    pub unique_columns: Vec<String>,
+    pub immutable_columns: Vec<String>,
+}
+
+pub(crate) fn parse_fields(
+    fields: &Punctuated<syn::Field, Token![,]>,
+) -> syn::Result<ParsedFields> {
+    let mut field_desc_tokens: Vec<TokenStream> = Vec::new();
+    let mut checks: Vec<TokenStream> = Vec::new();
+    let mut enum_desc_tokens: Vec<TokenStream> = Vec::new();
+    // (field name, column name, declared as `Option<T>`) of every field
+    let mut columns: Vec<(String, String, bool)> = Vec::new();
+    let mut sql_types: Vec<Option<ColumnType>> = Vec::new();
+    let mut field_primary_key: Vec<String> = Vec::new();
+    let mut unique_columns: Vec<String> = Vec::new();
+    let mut immutable_columns: Vec<String> = Vec::new();
+
+    for field in fields {
+        let ident = match &field.ident {
+            Some(i) => i.to_string(),
+            None => {
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    "struct must have named fields",
+                ))
+            }
+        };
+        let field_ty = &field.ty;
+        let typ = field_ty.to_token_stream().to_string();
+        let mut sql_typ: Option<ColumnType> = None;
+        let mut nullable_attr: Option<proc_macro2::Span> = None;
+        let mut primary_key = false;
+        let mut default: Option<(ColumnDefault, proc_macro2::Span)> = None;
+        let mut immutable = false;
+        let mut unique = false;
+        let mut col_name: String = ident.clone();
+
+        for field_attr in &field.attrs {
+            if !field_attr.path().is_ident("graphix") {
+                continue;
+            }
+
+            let args: Punctuated<syn::Meta, Token![,]> =
+                field_attr.parse_args_with(Punctuated::parse_terminated)?;
+
+            for arg in &args {
+                match FieldAttribute::from_meta(arg) {
+                    Ok(att) => match att {
+                        FieldAttribute::Unique => unique = true,
+                        FieldAttribute::Immutable => immutable = true,
+                        FieldAttribute::Nullable => nullable_attr = Some(arg.span()),
+                        FieldAttribute::PrimaryKey => {
+                            if option_inner(field_ty).is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    arg,
+                                    "primary key columns cannot be `Option<T>`",
+                                ));
+                            }
+
+                            primary_key = true;
+                        }
+                        FieldAttribute::ColumnName(name) => col_name = name,
+                        FieldAttribute::SqlType(typ) => sql_typ = Some(typ),
+                        FieldAttribute::Default(value) => {
+                            if default.is_some() {
+                                return Err(syn::Error::new_spanned(
+                                    arg,
+                                    "only one of `default` and `default_expr` can be set",
+                                ));
+                            }
+
+                            default = Some((value, arg.span()));
+                        }
+                    },
+
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        if primary_key {
+            field_primary_key.push(col_name.clone());
+        }
+        if unique {
+            unique_columns.push(col_name.clone());
+        }
+        if immutable {
+            immutable_columns.push(col_name.clone());
+        }
+        columns.push((ident.clone(), col_name.clone(), option_inner(field_ty).is_some()));
+        sql_types.push(sql_typ.clone());
+
+        let (sql_typ, nullable, unsigned) = match sql_typ {
+            // an explicit type says nothing about nullability, so fall back to the field's syntax
+            Some(typ) => {
+                let nullable = option_inner(field_ty).is_some();
+                if let (Some(attr), false) = (nullable_attr, nullable) {
+                    return Err(syn::Error::new(
+                        attr,
+                        "`nullable` can only be used on `Option<T>` fields",
+                    ));
+                }
+
+                if let Some((value, span)) = &default {
+                    if !typ.accepts_default(value) {
+                        return Err(syn::Error::new(
+                            *span,
+                            format!("default is not a valid value for a `{}` column", typ),
+                        ));
+                    }
+                }
+
+                // an explicit type is taken as is, signed
+                (quote! { #typ }, quote! { #nullable }, quote! { None })
+            }
+
+            None => {
+                let sql_type_trait = quote_spanned! { field_ty.span()=>
+                    <#field_ty as graphix::sql::postgres::SqlType>
+                };
+
+                if let Some(attr) = nullable_attr {
+                    checks.push(quote_spanned! { attr=>
+                        const _: () = assert!(
+                            #sql_type_trait::NULLABLE,
+                            "`nullable` can only be used on `Option<T>` fields",
+                        );
+                    });
+                }
+
+                let literal = match &default {
+                    Some((ColumnDefault::Bool(_), span)) => Some((quote! { Bool }, span)),
+                    Some((ColumnDefault::Int(_), span)) => Some((quote! { Int }, span)),
+                    Some((ColumnDefault::Float(_), span)) => Some((quote! { Float }, span)),
+                    // strings are checked below, against enum labels and `char` lengths too
+                    Some((ColumnDefault::Str(_) | ColumnDefault::Expr(_), _)) | None => None,
+                };
+                if let Some((literal, span)) = literal {
+                    checks.push(quote_spanned! { *span=>
+                        const _: fn() = {
+                            fn accepts_default<T: graphix::sql::postgres::AcceptsDefault<graphix::sql::postgres::literal::#literal> + ?Sized>() {}
+                            accepts_default::<#field_ty>
+                        };
+                    });
+                }
+
+                if let Some((ColumnDefault::Int(value), span)) = &default {
+                    let value = *value as i128;
+                    checks.push(quote_spanned! { *span=>
+                        const _: () = assert!(
+                            graphix::sql::postgres::accepts_int_default::<#field_ty>(#value),
+                            "default is out of range for the field's type",
+                        );
+                    });
+                }
+                                if let Some((ColumnDefault::Str(value), span)) = &default {
+                    checks.push(quote_spanned! { *span=>
+                        const _: () = assert!(
+                            graphix::sql::postgres::accepts_str_default::<#field_ty>(#value),
+                            "default is not a valid value for the field's column",
+                        );
+                    });
+                }
+
+                enum_desc_tokens.push(quote! {
+                    if let Some(e) = #sql_type_trait::enum_descriptor() {
+                        if !enums.iter().any(|d: &graphix::descriptor::EnumDescriptor| d.name == e.name) {
+                            enums.push(e);
+                        }
+                    }
+                });
+
+                (
+                    quote! { #sql_type_trait::column_type() },
+                    quote! { #sql_type_trait::NULLABLE },
+                    quote! { #sql_type_trait::UNSIGNED },
+                )
+            }
+        };
+
+        let default = match default {
+            Some((value, _)) => quote! { Some(#value) },
+            None => quote! { None },
+        };
+
+        field_desc_tokens.push(quote! {
+            graphix::descriptor::EntityFieldDescriptor {
+                name: #ident.to_string(),
+                typ: #typ.to_string(),
+                sql_type: graphix::sql::LogicalType::from(#sql_typ),
+                column_name: #col_name.to_string(),
+                unique: #unique,
+                immutable: #immutable,
+                nullable: #nullable,
+                default: #default,
+                unsigned: #unsigned,
+            },
+        });
+    }
+
+    Ok(ParsedFields {
+        field_desc_tokens,
+        checks,
+        enum_desc_tokens,
+        columns,
+        sql_types,
+        field_primary_key,
+        unique_columns,
+        immutable_columns,
+    })
+}
+
 pub fn entity_inner(ast: &DeriveInput) -> syn::Result<TokenStream> {
     let name = &ast.ident;
 
@@ -132,13 +634,17 @@ pub fn entity_inner(ast: &DeriveInput) -> syn::Result<TokenStream> {
     let struct_name = name.to_string();
     let mut table_name = format!("{}s", name.to_string().to_lowercase());
     let mut schema_name = String::from("schema.public");
+    let mut struct_primary_key: Option<Vec<syn::LitStr>> = None;
+    let mut no_primary_key: Option<proc_macro2::Span> = None;
+    let mut struct_indexes: Vec<IndexAttribute> = Vec::new();
+    let mut edges: Vec<EdgeAttribute> = Vec::new();
 
     for struct_attr in &ast.attrs {
         if !struct_attr.path().is_ident("graphix") {
             continue;
         }
 
-        let args: Punctuated<MetaNameValue, Token![,]> =
+        let args: Punctuated<Meta, Token![,]> =
             struct_attr.parse_args_with(Punctuated::parse_terminated)?;
 
         for arg in &args {
@@ -146,6 +652,10 @@ pub fn entity_inner(ast: &DeriveInput) -> syn::Result<TokenStream> {
                 Ok(att) => match att {
                     StructAttribute::TableName(name) => table_name = name,
                     StructAttribute::SchemaName(name) => schema_name = name,
+                    StructAttribute::PrimaryKey(columns) => struct_primary_key = Some(columns),
+                    StructAttribute::NoPrimaryKey => no_primary_key = Some(arg.span()),
+                    StructAttribute::Index(index) => struct_indexes.push(index),
+                    StructAttribute::Edge(edge) => edges.push(edge),
                 },
 
                 Err(e) => return Err(e),
@@ -154,74 +664,279 @@ pub fn entity_inner(ast: &DeriveInput) -> syn::Result<TokenStream> {
     }
 
     // parse field attrs
+    let ParsedFields {
+        field_desc_tokens,
+        mut checks,
+        enum_desc_tokens,
+        columns,
+        sql_types,
+        field_primary_key,
+        unique_columns,
+        immutable_columns,
+    } = parse_fields(fields)?;
+
+    let find_column = |key: &syn::LitStr| {
+        let value = key.value();
+        columns
+            .iter()
+            .find(|(field, col, _)| *field == value || *col == value)
+            .ok_or_else(|| {
+                syn::Error::new_spanned(key, format!("`{}` is not a field of `{}`", value, name))
+            })
+    };
 
-    let mut field_desc_tokens: Vec<TokenStream> = Vec::new();
+    // resolve the primary key
+    let primary_key: Vec<String> = match (struct_primary_key, field_primary_key.is_empty()) {
+        (Some(keys), true) => {
+            let mut resolved = Vec::new();
+            for key in keys {
+                let value = key.value();
+                let (_, col_name, optional) = find_column(&key)?;
+
+                if *optional {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        "primary key columns cannot be `Option<T>`",
+                    ));
+                }
+                if resolved.contains(col_name) {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        format!("`{}` is already part of the primary key", value),
+                    ));
+                }
 
-    for field in fields {
-        let ident = match &field.ident {
-            Some(i) => i.to_string(),
-            None => {
-                return Err(syn::Error::new_spanned(
-                    &field.ident,
-                    "struct must have named fields to derive `Entity`",
-                ))
+                resolved.push(col_name.clone());
             }
-        };
-        let typ = field.ty.to_token_stream().to_string();
-        let sql_typ = ColumnType::try_from(&field.ty)?;
-        let mut immutable = false;
-        let mut unique = false;
-        let mut nullable = false;
-        let mut col_name: String = ident.clone();
+            resolved
+        }
 
-        for field_attr in &field.attrs {
-            if !field_attr.path().is_ident("graphix") {
+        (Some(keys), false) => {
+            return Err(syn::Error::new_spanned(
+                &keys[0],
+                "primary key is already declared with `#[graphix(primary_key)]` on a field",
+            ))
+        }
+
+        (None, _) => field_primary_key,
+    };
+
+    match (primary_key.is_empty(), no_primary_key) {
+        (true, None) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "entity has no primary key, mark a field with `#[graphix(primary_key)]`, \
+                 declare `#[graphix(primary_key = [\"a\", \"b\"])]` on the struct, \
+                 or opt out with `#[graphix(no_primary_key)]`",
+            ))
+        }
+        (false, Some(span)) => {
+            return Err(syn::Error::new(
+                span,
+                "`no_primary_key` cannot be used on an entity with a primary key",
+            ))
+        }
+        _ => {}
+    }
+
+    // resolve edges, the foreign key of an edge is on this table, for an inverse edge it's on
+    // the target's so its columns can't be checked here
+    let mut edge_tokens: Vec<TokenStream> = Vec::new();
+    // one-to-one edges need their foreign key to be unique
+    let mut unique_column_sets: Vec<Vec<String>> =
+        unique_columns.iter().map(|col| vec![col.clone()]).collect();
+
+    let mut join_table_tokens: Vec<TokenStream> = Vec::new();
+
+    for edge in &edges {
+        if edge.m2m && primary_key.is_empty() {
+            return Err(syn::Error::new(
+                edge.span,
+                "`m2m` edges can only be declared on entities with a primary key",
+            ));
+        }
+
+        let mut cols = Vec::new();
+        for key in &edge.fields {
+            if edge.inverse {
+                cols.push(key.value());
                 continue;
             }
 
-            let args: Punctuated<syn::Meta, Token![,]> =
-                field_attr.parse_args_with(Punctuated::parse_terminated)?;
+            let (_, col_name, optional) = find_column(key)?;
+            let sets_null = [edge.on_delete, edge.on_update].contains(&Some(ReferenceAction::SetNull));
+            if sets_null && !optional {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "`set_null` requires the foreign key field to be `Option<T>`",
+                ));
+            }
 
-            for arg in &args {
-                match FieldAttribute::from_meta(arg) {
-                    Ok(att) => match att {
-                        FieldAttribute::Unique => unique = true,
-                        FieldAttribute::Immutable => immutable = true,
-                        FieldAttribute::Nullable => nullable = true,
-                        FieldAttribute::ColumnName(name) => col_name = name,
-                    },
+            cols.push(col_name.clone());
+        }
 
-                    Err(e) => return Err(e),
+        if edge.unique && !edge.inverse && !unique_column_sets.contains(&cols) {
+            unique_column_sets.push(cols.clone());
+        }
+
+        checks.push(edge.check_tokens(name));
+        edge_tokens.push(edge.descriptor_tokens(name, &table_name, &cols, &primary_key));
+        join_table_tokens.extend(edge.join_table_tokens(name, &table_name, &schema_name));
+    }
+
+    // resolve indexes, field level `unique` is shorthand for a single column unique index
+    let mut index_names: Vec<String> = Vec::new();
+    let mut index_tokens: Vec<TokenStream> = Vec::new();
+
+    for cols in &unique_column_sets {
+        let index_name = format!("idx_{}_{}_unique", table_name, cols.join("_"));
+        index_tokens.push(quote! {
+            graphix::descriptor::IndexDescriptor {
+                name: #index_name.to_string(),
+                unique: true,
+                method: None,
+                parts: vec![#(graphix::descriptor::IndexPart::Column(#cols.to_string())),*],
+                predicate: None,
+            },
+        });
+        index_names.push(index_name);
+    }
+
+    for index in &struct_indexes {
+        let mut cols = Vec::new();
+        let mut part_tokens = Vec::new();
+        for part in &index.parts {
+            part_tokens.push(match part {
+                IndexPartAttribute::Column(key) => {
+                    let col = find_column(key)?.1.clone();
+                    cols.push(col.clone());
+                    quote! { graphix::descriptor::IndexPart::Column(#col.to_string()) }
                 }
+                IndexPartAttribute::Expr(expr) => {
+                    quote! { graphix::descriptor::IndexPart::Expr(#expr.to_string()) }
+                }
+            });
+        }
+
+        let index_name = match &index.name {
+            Some(n) => n.clone(),
+            None if cols.len() < index.parts.len() => {
+                return Err(syn::Error::new(
+                    index.span,
+                    "expression indexes must be given a `name`",
+                ))
             }
+            None => format!(
+                "idx_{}_{}{}",
+                table_name,
+                cols.join("_"),
+                if index.unique { "_unique" } else { "" }
+            ),
+        };
+
+        if index_names.contains(&index_name) {
+            return Err(syn::Error::new(
+                index.span,
+                format!("duplicate index name `{}`", index_name),
+            ));
         }
 
-        field_desc_tokens.push(quote! {
-            graphix::descriptor::EntityFieldDescriptor {
-                name: #ident.to_string(),
-                typ: #typ.to_string(),
-                sql_type: #sql_typ,
-                column_name: #col_name.to_string(),
+        let unique = index.unique;
+        let method = match &index.method {
+            Some(m) => quote! { Some(#m) },
+            None => quote! { None },
+        };
+        let predicate = match &index.predicate {
+            Some(p) => quote! { Some(#p.to_string()) },
+            None => quote! { None },
+        };
+        index_tokens.push(quote! {
+            graphix::descriptor::IndexDescriptor {
+                name: #index_name.to_string(),
                 unique: #unique,
-                immutable: #immutable,
-                nullable: #nullable,
+                method: #method,
+                parts: vec![#(#part_tokens),*],
+                predicate: #predicate,
             },
         });
+        index_names.push(index_name);
     }
 
+    // the field descriptors of the primary key, in key order
+    let primary_key_field_tokens = primary_key.iter().map(|key| {
+        let i = columns
+            .iter()
+            .position(|(_, col, _)| col == key)
+            .expect("primary key columns are resolved from the fields");
+        &field_desc_tokens[i]
+    });
+
+    let query_tokens = query_tokens(
+        ast,
+        fields,
+        &columns,
+        &sql_types,
+        &primary_key,
+        &unique_columns,
+        &immutable_columns,
+    );
+
     let output = quote! {
+        #(#checks)*
+
+        impl graphix::entity::EntityTable for #name {
+            const NAME: &'static str = #struct_name;
+            const TABLE_NAME: &'static str = #table_name;
+            const SCHEMA_NAME: &'static str = #schema_name;
+            const PRIMARY_KEY: &'static [&'static str] = &[#(#primary_key),*];
+
+            fn primary_key_fields() -> Vec<graphix::descriptor::EntityFieldDescriptor> {
+                vec![
+                    #(#primary_key_field_tokens)*
+                ]
+            }
+        }
+
         impl graphix::entity::Entity for #name {
-            fn entity_descriptor(&self) -> graphix::descriptor::EntityDescriptor {
-                graphix::descriptor::EntityDescriptor {
+            fn descriptor() -> &'static graphix::descriptor::EntityDescriptor {
+                static DESCRIPTOR: std::sync::OnceLock<graphix::descriptor::EntityDescriptor> =
+                    std::sync::OnceLock::new();
+
+                DESCRIPTOR.get_or_init(|| graphix::descriptor::EntityDescriptor {
                     name: #struct_name.to_string(),
                     table_name: #table_name.to_string(),
                     schema_name: #schema_name.to_string(),
                     fields: vec![
                         #(#field_desc_tokens)*
                     ],
-                }
+                    primary_key: vec![#(#primary_key.to_string()),*],
+                    indexes: vec![
+                        #(#index_tokens)*
+                    ],
+                    edges: vec![
+                        #(#edge_tokens)*
+                    ],
+                    enums: {
+                        let mut enums = Vec::new();
+                        #(#enum_desc_tokens)*
+                        enums
+                    },
+                    join_tables: vec![
+                        #(#join_table_tokens)*
+                    ],
+                })
+            }
+
+            fn entity_descriptor(&self) -> graphix::descriptor::EntityDescriptor {
+                <Self as graphix::entity::Entity>::descriptor().clone()
             }
         }
+
+        graphix::inventory::submit! {
+            graphix::schema::EntityRegistration::new::<#name>()
+        }
+
+        #query_tokens
     };
 
     Ok(output)
@@ -1 +1,7 @@
+pub mod edge;
+pub mod edge_schema;
 pub mod entity;
+pub mod query;
+pub mod sql_enum;
+
+pub(crate) use graphix_models::codegen::to_snake_case;
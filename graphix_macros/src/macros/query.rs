@@ -0,0 +1,224 @@
+use graphix_models::sql::postgres::ColumnType;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{punctuated::Punctuated, DeriveInput, Token};
+
+/// A `<Name>Columns` type with a `graphix::query::Column` for every field and the entity's create
+/// and update builders, along with `query()`, the mutations and a `get_by_` for every unique field
+/// and single column primary key on the entity. Everything is emitted next to the entity, so field
+/// types resolve as they do in the struct, even inside a function body.
+///
+/// `columns` are the (field name, column name, declared as `Option<T>`) of `fields`, in order,
+/// and `sql_types` their explicit `sql_type`s, which decide the predicates of their columns.
+pub(crate) fn query_tokens(
+    ast: &DeriveInput,
+    fields: &Punctuated<syn::Field, Token![,]>,
+    columns: &[(String, String, bool)],
+    sql_types: &[Option<ColumnType>],
+    primary_key: &[String],
+    unique_columns: &[String],
+    immutable_columns: &[String],
+) -> TokenStream {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let columns_ty = format_ident!("{}Columns", name);
+    let doc = format!("The columns of [`{}`], for filtering and ordering.", name);
+
+    let create = format_ident!("{}Create", name);
+    let update = format_ident!("{}Update", name);
+    let update_one = format_ident!("{}UpdateOne", name);
+
+    let mut column_fns = Vec::new();
+    let mut create_setters = Vec::new();
+    let mut update_setters = Vec::new();
+    let mut lookups = Vec::new();
+
+    for (i, ((field, (_, col_name, optional)), sql_type)) in
+        fields.iter().zip(columns).zip(sql_types).enumerate()
+    {
+        let ident = field.ident.as_ref().expect("fields are named");
+        let field_ty = &field.ty;
+        let field_name = ident.to_string();
+        let field_name = field_name.trim_start_matches("r#");
+
+        let mutability = if immutable_columns.contains(col_name) {
+            quote!(graphix::mutation::Immutable)
+        } else {
+            quote!(graphix::mutation::Mutable)
+        };
+        // the field's own type decides unless its `sql_type` says otherwise
+        let kind = match sql_type {
+            Some(typ) if typ.is_text() => quote!(graphix::query::kind::Text),
+            Some(typ) if typ.is_ordered() => quote!(graphix::query::kind::Ordered),
+            Some(_) => quote!(graphix::query::kind::Other),
+            None => quote!(#field_ty),
+        };
+        column_fns.push(quote! {
+            pub fn #ident() -> graphix::query::Column<#name, #field_ty, #mutability, #kind> {
+                graphix::query::Column::new(
+                    &<#name as graphix::entity::Entity>::descriptor().fields[#i],
+                )
+            }
+        });
+
+        let set = format_ident!("set_{}", field_name);
+        let setter = quote! {
+            pub fn #set<V: graphix::query::IntoValue<#field_ty>>(self, value: V) -> Self {
+                Self { inner: self.inner.set(#columns_ty::#ident(), value) }
+            }
+        };
+        create_setters.push(setter.clone());
+
+        // immutable fields are only set on create
+        if !immutable_columns.contains(col_name) {
+            update_setters.push(setter);
+
+            if *optional {
+                let clear = format_ident!("clear_{}", field_name);
+                update_setters.push(quote! {
+                    pub fn #clear(self) -> Self {
+                        Self { inner: self.inner.clear(#columns_ty::#ident()) }
+                    }
+                });
+            }
+        }
+
+        // a primary key of one column identifies a row as well as a unique field
+        if unique_columns.contains(col_name) || matches!(primary_key, [key] if key == col_name) {
+            let get_by = format_ident!("get_by_{}", field_name);
+            lookups.push(quote! {
+                pub fn #get_by<V: graphix::query::IntoValue<#field_ty>>(
+                    value: V,
+                ) -> graphix::query::Query<#name> {
+                    graphix::query::Query::new()
+                        .filter(#columns_ty::#ident().eq(value))
+                        .limit(1)
+                }
+            });
+        }
+    }
+
+    // only a single column key identifies a row by one value
+    let primary_key_field = match primary_key {
+        [key] => columns
+            .iter()
+            .position(|(_, col, _)| col == key)
+            .map(|i| &fields[i]),
+        _ => None,
+    };
+    let update_one_tokens = primary_key_field.map(|field| {
+        let ident = &field.ident;
+        let field_ty = &field.ty;
+        let struct_doc = format!(
+            "An update of one [`{}`] by its primary key, returning the updated row.",
+            name
+        );
+
+        quote! {
+            #[doc = #struct_doc]
+            #vis struct #update_one {
+                inner: graphix::mutation::Update<#name>,
+            }
+
+            #[allow(dead_code)]
+            impl #update_one {
+                #(#update_setters)*
+
+                pub fn to_sql(
+                    &self,
+                ) -> Result<(String, Vec<graphix::query::Value>), graphix::mutation::MutationError> {
+                    self.inner.to_sql()
+                }
+            }
+
+            #[allow(dead_code)]
+            impl #name {
+                pub fn update_one<V: graphix::query::IntoValue<#field_ty>>(id: V) -> #update_one {
+                    #update_one {
+                        inner: graphix::mutation::Update::one(#columns_ty::#ident().eq(id)),
+                    }
+                }
+            }
+        }
+    });
+
+    let create_doc = format!("An insert of one [`{}`], returning the inserted row.", name);
+    let update_doc = format!(
+        "An update of every [`{}`] matching the filters. Immutable fields have no setter.",
+        name
+    );
+
+    quote! {
+        #[doc = #doc]
+        #vis struct #columns_ty;
+
+        #[allow(dead_code)]
+        impl #columns_ty {
+            #(#column_fns)*
+        }
+
+        #[doc = #create_doc]
+        #vis struct #create {
+            inner: graphix::mutation::Create<#name>,
+        }
+
+        #[allow(dead_code)]
+        impl #create {
+            #(#create_setters)*
+
+            pub fn to_sql(
+                &self,
+            ) -> Result<(String, Vec<graphix::query::Value>), graphix::mutation::MutationError> {
+                self.inner.to_sql()
+            }
+        }
+
+        #[doc = #update_doc]
+        #vis struct #update {
+            inner: graphix::mutation::Update<#name>,
+        }
+
+        #[allow(dead_code)]
+        impl #update {
+            #(#update_setters)*
+
+            pub fn filter(self, predicate: graphix::query::Predicate<#name>) -> Self {
+                Self { inner: self.inner.filter(predicate) }
+            }
+
+            /// Updates every row when there are no filters, which is otherwise an error.
+            pub fn all(self) -> Self {
+                Self { inner: self.inner.all() }
+            }
+
+            pub fn to_sql(
+                &self,
+            ) -> Result<(String, Vec<graphix::query::Value>), graphix::mutation::MutationError> {
+                self.inner.to_sql()
+            }
+        }
+
+        #update_one_tokens
+
+        #[allow(dead_code)]
+        impl #name {
+            pub fn query() -> graphix::query::Query<#name> {
+                graphix::query::Query::new()
+            }
+
+            pub fn create() -> #create {
+                #create { inner: graphix::mutation::Create::new() }
+            }
+
+            pub fn update() -> #update {
+                #update { inner: graphix::mutation::Update::new() }
+            }
+
+            pub fn delete() -> graphix::mutation::Delete<#name> {
+                graphix::mutation::Delete::new()
+            }
+
+            #(#lookups)*
+        }
+    }
+}
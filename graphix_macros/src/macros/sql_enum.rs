@@ -0,0 +1,187 @@
+use crate::macros::to_snake_case;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{punctuated::Punctuated, DeriveInput, Expr, Lit, MetaNameValue, Token};
+
+enum EnumAttribute {
+    Name(String),
+    SchemaName(String),
+}
+impl EnumAttribute {
+    pub fn from_meta(arg: &MetaNameValue) -> syn::Result<Self> {
+        let name = match arg.path.get_ident() {
+            Some(i) => i.to_string(),
+            None => return Err(syn::Error::new_spanned(arg, "invalid formatting")),
+        };
+
+        let value = str_value(arg)?;
+
+        match name.as_str() {
+            "name" => Ok(Self::Name(value)),
+            "schema_name" => Ok(Self::SchemaName(value)),
+            _ => Err(syn::Error::new_spanned(
+                arg,
+                format!("unknown attribute `{}`", name),
+            )),
+        }
+    }
+}
+
+enum VariantAttribute {
+    Rename(String),
+}
+impl VariantAttribute {
+    pub fn from_meta(arg: &MetaNameValue) -> syn::Result<Self> {
+        let name = match arg.path.get_ident() {
+            Some(i) => i.to_string(),
+            None => return Err(syn::Error::new_spanned(arg, "invalid formatting")),
+        };
+
+        let value = str_value(arg)?;
+
+        match name.as_str() {
+            "rename" => Ok(Self::Rename(value)),
+            _ => Err(syn::Error::new_spanned(
+                arg,
+                format!("unknown attribute `{}`", name),
+            )),
+        }
+    }
+}
+
+fn str_value(arg: &MetaNameValue) -> syn::Result<String> {
+    let value = match &arg.value {
+        Expr::Lit(s) => match &s.lit {
+            Lit::Str(s) => s.value(),
+            _ => return Err(syn::Error::new_spanned(s, "invalid formatting")),
+        },
+        _ => return Err(syn::Error::new_spanned(&arg.value, "invalid formatting")),
+    };
+
+    if value.is_empty() {
+        return Err(syn::Error::new_spanned(arg, "value cannot be empty"));
+    }
+
+    Ok(value)
+}
+
+pub fn sql_enum_inner(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+
+    let variants = match &ast.data {
+        syn::Data::Enum(e) => &e.variants,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "only enums can derive `SqlEnum`",
+            ))
+        }
+    };
+
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "enums deriving `SqlEnum` must have at least one variant",
+        ));
+    }
+
+    // parse enum attrs
+    let mut enum_name = to_snake_case(&name.to_string());
+    let mut schema_name = String::from("schema.public");
+
+    for attr in &ast.attrs {
+        if !attr.path().is_ident("graphix") {
+            continue;
+        }
+
+        let args: Punctuated<MetaNameValue, Token![,]> =
+            attr.parse_args_with(Punctuated::parse_terminated)?;
+
+        for arg in &args {
+            match EnumAttribute::from_meta(arg)? {
+                EnumAttribute::Name(name) => enum_name = name,
+                EnumAttribute::SchemaName(name) => schema_name = name,
+            }
+        }
+    }
+
+    // parse variants
+    let mut idents = Vec::new();
+    let mut labels: Vec<String> = Vec::new();
+
+    for variant in variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`SqlEnum` can only be derived for enums without fields",
+            ));
+        }
+
+        let mut label = to_snake_case(&variant.ident.to_string());
+
+        for attr in &variant.attrs {
+            if !attr.path().is_ident("graphix") {
+                continue;
+            }
+
+            let args: Punctuated<MetaNameValue, Token![,]> =
+                attr.parse_args_with(Punctuated::parse_terminated)?;
+
+            for arg in &args {
+                match VariantAttribute::from_meta(arg)? {
+                    VariantAttribute::Rename(name) => label = name,
+                }
+            }
+        }
+
+        if labels.contains(&label) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("duplicate enum label `{}`", label),
+            ));
+        }
+
+        idents.push(&variant.ident);
+        labels.push(label);
+    }
+
+    let output = quote! {
+        impl graphix::sql::postgres::SqlType for #name {
+            fn column_type() -> graphix::sql::postgres::ColumnType {
+                graphix::sql::postgres::ColumnType::Enum(#enum_name.to_string())
+            }
+
+            fn enum_descriptor() -> Option<graphix::descriptor::EnumDescriptor> {
+                Some(graphix::descriptor::EnumDescriptor {
+                    name: #enum_name.to_string(),
+                    schema_name: #schema_name.to_string(),
+                    values: vec![#(#labels.to_string()),*],
+                })
+            }
+        }
+
+        impl graphix::sql::postgres::AcceptsDefault<graphix::sql::postgres::literal::Str> for #name {
+            const LABELS: Option<&'static [&'static str]> =
+                Some(<Self as graphix::sql::postgres::SqlEnum>::VALUES);
+        }
+
+        impl graphix::sql::postgres::SqlEnum for #name {
+            const VALUES: &'static [&'static str] = &[#(#labels),*];
+
+            fn as_label(&self) -> &'static str {
+                match self {
+                    #(Self::#idents => #labels,)*
+                }
+            }
+
+            fn from_label(label: &str) -> Option<Self> {
+                match label {
+                    #(#labels => Some(Self::#idents),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+
+    Ok(output)
+}
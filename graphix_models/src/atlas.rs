@@ -0,0 +1,464 @@
+//! Reads Atlas HCL back into descriptors, the inverse of `as_atlas_hcl`, so databases already
+//! described by Atlas can be brought into graphix.
+
+use crate::codegen::{entity_name, field_ident, rust_type, to_snake_case};
+use crate::descriptor::{
+    ColumnDefault, EdgeCardinality, EdgeDescriptor, EntityDescriptor, EntityFieldDescriptor,
+    EnumDescriptor, IndexDescriptor, IndexMethod, IndexPart, ReferenceAction,
+};
+use crate::schema::Schema;
+use crate::sql::postgres::ColumnType;
+use hcl::expr::{Operation, UnaryOperator};
+use hcl::{Block, Body, Expression, TraversalOperator};
+use std::fmt::Formatter;
+
+#[derive(Debug)]
+pub enum AtlasError {
+    Parse(hcl::Error),
+    /// A block is missing something graphix needs or has a value it can't read. `context` is the
+    /// path of the block, e.g. `table.users.column.id`.
+    Invalid {
+        context: String,
+        reason: String,
+    },
+}
+impl AtlasError {
+    fn invalid(context: &str, reason: impl Into<String>) -> Self {
+        Self::Invalid {
+            context: context.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+impl std::fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid HCL: {}", e),
+            Self::Invalid { context, reason } => write!(f, "`{}`: {}", context, reason),
+        }
+    }
+}
+impl std::error::Error for AtlasError {}
+impl From<hcl::Error> for AtlasError {
+    fn from(e: hcl::Error) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl Schema {
+    /// Reads the `enum` and `table` blocks of an Atlas HCL document. Other blocks, such as views
+    /// or functions, are skipped. Entities are named after their tables, `User` for `users`.
+    pub fn from_atlas_hcl(hcl: &str) -> Result<Self, AtlasError> {
+        Self::from_atlas_body(&hcl::parse(hcl)?)
+    }
+
+    pub fn from_atlas_body(body: &Body) -> Result<Self, AtlasError> {
+        let mut enums = Vec::new();
+        let mut tables = Vec::new();
+
+        for block in body.blocks() {
+            match block.identifier() {
+                "enum" => enums.push(EnumDescriptor::from_atlas_hcl(block)?),
+                "table" => tables.push(EntityDescriptor::from_atlas_hcl(block)?),
+                _ => {}
+            }
+        }
+
+        let mut schema = Schema::new();
+        for mut table in tables {
+            // the enums the table's columns depend on, like the derive collects them
+            for field in &table.fields {
+                if let Some(name) = field.sql_type.enum_name() {
+                    let found = enums.iter().find(|e| e.name == name);
+                    if let Some(e) = found.filter(|e| !table.enums.contains(e)) {
+                        table.enums.push(e.clone());
+                    }
+                }
+            }
+
+            schema.add(&table);
+        }
+        for e in enums {
+            schema.add_enum(e);
+        }
+
+        Ok(schema)
+    }
+}
+
+impl EnumDescriptor {
+    pub fn from_atlas_hcl(block: &Block) -> Result<Self, AtlasError> {
+        let name = label(block)?;
+        let context = format!("enum.{}", name);
+
+        let values = match attribute(&block.body, "values") {
+            Some(Expression::Array(values)) => values
+                .iter()
+                .map(|v| match v {
+                    Expression::String(s) => Ok(s.clone()),
+                    _ => Err(AtlasError::invalid(&context, "enum values must be strings")),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err(AtlasError::invalid(&context, "missing `values`")),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            schema_name: schema_ref(&block.body, &context)?,
+            values,
+        })
+    }
+}
+
+impl EntityDescriptor {
+    /// Reads a `table` block. Its `enums` are left empty, [`Schema::from_atlas_hcl`] fills them
+    /// in from the document's `enum` blocks.
+    pub fn from_atlas_hcl(block: &Block) -> Result<Self, AtlasError> {
+        let table_name = label(block)?.to_string();
+        let context = format!("table.{}", table_name);
+        let schema_name = schema_ref(&block.body, &context)?;
+
+        let mut fields = Vec::new();
+        let mut primary_key = Vec::new();
+        let mut edges = Vec::new();
+        let mut indexes = Vec::new();
+
+        for child in block.body.blocks() {
+            match child.identifier() {
+                "column" => fields.push(column(child, &context)?),
+                "primary_key" => primary_key = column_refs(&child.body, "columns", &context)?,
+                "foreign_key" => {
+                    edges.push(foreign_key(child, &table_name, &schema_name, &context)?)
+                }
+                "index" => indexes.push(index(child, &context)?),
+                _ => {}
+            }
+        }
+
+        let plain_unique = |cols: &[String]| {
+            indexes.iter().any(|i: &IndexDescriptor| {
+                i.unique
+                    && i.predicate.is_none()
+                    && i.parts.len() == cols.len()
+                    && i.parts
+                        .iter()
+                        .zip(cols)
+                        .all(|(part, col)| *part == IndexPart::Column(col.clone()))
+            })
+        };
+
+        // `unique` on a field is a single column unique index with the name the derive gives it
+        for field in &mut fields {
+            let name = format!("idx_{}_{}_unique", table_name, field.column_name);
+            field.unique = indexes.iter().any(|i| {
+                i.name == name
+                    && i.unique
+                    && i.method.is_none()
+                    && i.predicate.is_none()
+                    && i.parts == [IndexPart::Column(field.column_name.clone())]
+            });
+        }
+        for edge in &mut edges {
+            if plain_unique(&edge.columns) {
+                edge.cardinality = EdgeCardinality::O2O;
+            }
+        }
+
+        Ok(Self {
+            name: entity_name(&table_name),
+            table_name,
+            schema_name,
+            fields,
+            primary_key,
+            indexes,
+            edges,
+            enums: vec![],
+            join_tables: vec![],
+        })
+    }
+}
+
+fn column(block: &Block, table: &str) -> Result<EntityFieldDescriptor, AtlasError> {
+    let column_name = label(block)?.to_string();
+    let context = format!("{}.column.{}", table, column_name);
+
+    let sql_type = match attribute(&block.body, "type") {
+        Some(expr) => ColumnType::from_atlas_expr(expr)
+            .map_err(|e| AtlasError::invalid(&context, e.to_string()))?,
+        None => return Err(AtlasError::invalid(&context, "missing `type`")),
+    };
+    let nullable = match attribute(&block.body, "null") {
+        Some(Expression::Bool(b)) => *b,
+        None => false,
+        Some(_) => return Err(AtlasError::invalid(&context, "`null` must be a bool")),
+    };
+    let default = attribute(&block.body, "default")
+        .map(|expr| column_default(expr, &context))
+        .transpose()?;
+
+    let (typ, _) = rust_type(&sql_type);
+    Ok(EntityFieldDescriptor {
+        name: field_ident(&column_name),
+        column_name,
+        typ: match nullable {
+            true => format!("Option<{}>", typ),
+            false => typ,
+        },
+        sql_type: sql_type.into(),
+        unique: false,
+        immutable: false,
+        nullable,
+        default,
+        unsigned: None,
+    })
+}
+
+fn column_default(expr: &Expression, context: &str) -> Result<ColumnDefault, AtlasError> {
+    match expr {
+        Expression::Bool(b) => Ok(ColumnDefault::Bool(*b)),
+        Expression::Number(n) => Ok(match n.as_i64() {
+            Some(i) if !n.is_f64() => ColumnDefault::Int(i),
+            _ => ColumnDefault::Float(n.to_string()),
+        }),
+        Expression::String(s) => Ok(ColumnDefault::Str(s.clone())),
+        Expression::FuncCall(call) if call.name.as_str() == "sql" => match call.args.as_slice() {
+            [Expression::String(sql)] => Ok(ColumnDefault::Expr(sql.clone())),
+            _ => Err(AtlasError::invalid(context, "`sql` takes a single string")),
+        },
+        Expression::Operation(op) => match &**op {
+            Operation::Unary(unary) if unary.operator == UnaryOperator::Neg => {
+                match column_default(&unary.expr, context)? {
+                    ColumnDefault::Int(i) => Ok(ColumnDefault::Int(-i)),
+                    ColumnDefault::Float(f) => Ok(ColumnDefault::Float(format!("-{}", f))),
+                    _ => Err(AtlasError::invalid(context, "only numbers can be negated")),
+                }
+            }
+            _ => Err(AtlasError::invalid(context, "unsupported `default`")),
+        },
+        _ => Err(AtlasError::invalid(context, "unsupported `default`")),
+    }
+}
+
+fn foreign_key(
+    block: &Block,
+    table: &str,
+    schema_name: &str,
+    context: &str,
+) -> Result<EdgeDescriptor, AtlasError> {
+    let context = format!("{}.foreign_key.{}", context, label(block)?);
+    let columns = column_refs(&block.body, "columns", &context)?;
+
+    // `table.groups.column.id`, `table.public.groups.column.id` across schemas, or `column.id`
+    // for a reference to the same table
+    let mut target: Option<(String, String)> = None;
+    let mut ref_columns = Vec::new();
+    for path in refs(&block.body, "ref_columns", &context)? {
+        let (target_schema, target_table, column) = match path.as_slice() {
+            [t, table, c, column] if t == "table" && c == "column" => {
+                (schema_name.to_string(), table.clone(), column.clone())
+            }
+            [t, schema, table, c, column] if t == "table" && c == "column" => {
+                (format!("schema.{}", schema), table.clone(), column.clone())
+            }
+            [c, column] if c == "column" => {
+                (schema_name.to_string(), table.to_string(), column.clone())
+            }
+            _ => {
+                return Err(AtlasError::invalid(
+                    &context,
+                    format!("invalid column reference `{}`", path.join(".")),
+                ))
+            }
+        };
+
+        if target.get_or_insert((target_schema.clone(), target_table.clone()))
+            != &(target_schema, target_table)
+        {
+            return Err(AtlasError::invalid(
+                &context,
+                "`ref_columns` must be in one table",
+            ));
+        }
+        ref_columns.push(column);
+    }
+
+    let Some((target_schema, target_table)) = target else {
+        return Err(AtlasError::invalid(&context, "missing `ref_columns`"));
+    };
+    if columns.len() != ref_columns.len() {
+        return Err(AtlasError::invalid(
+            &context,
+            "`columns` and `ref_columns` must have the same length",
+        ));
+    }
+
+    let action = |name: &str| -> Result<Option<ReferenceAction>, AtlasError> {
+        match attribute(&block.body, name) {
+            Some(expr) => expr_path(expr)
+                .map(|path| path.join("."))
+                .ok_or_else(|| AtlasError::invalid(&context, format!("invalid `{}`", name)))?
+                .parse()
+                .map(Some)
+                .map_err(|e: String| AtlasError::invalid(&context, e)),
+            None => Ok(None),
+        }
+    };
+
+    let target = entity_name(&target_table);
+    Ok(EdgeDescriptor {
+        name: to_snake_case(&target),
+        target,
+        target_table,
+        target_schema,
+        cardinality: EdgeCardinality::M2O,
+        inverse: false,
+        columns,
+        ref_columns,
+        on_delete: action("on_delete")?,
+        on_update: action("on_update")?,
+        through: None,
+    })
+}
+
+fn index(block: &Block, table: &str) -> Result<IndexDescriptor, AtlasError> {
+    let name = label(block)?.to_string();
+    let context = format!("{}.index.{}", table, name);
+
+    let unique = matches!(
+        attribute(&block.body, "unique"),
+        Some(Expression::Bool(true))
+    );
+    let method = match attribute(&block.body, "type") {
+        Some(expr) => Some(
+            expr_path(expr)
+                .map(|path| path.join("."))
+                .ok_or_else(|| AtlasError::invalid(&context, "invalid `type`"))?
+                .parse::<IndexMethod>()
+                .map_err(|e| AtlasError::invalid(&context, e))?,
+        ),
+        None => None,
+    };
+    let predicate = match attribute(&block.body, "where") {
+        Some(Expression::String(s)) => Some(s.clone()),
+        Some(_) => return Err(AtlasError::invalid(&context, "`where` must be a string")),
+        None => None,
+    };
+
+    let mut parts: Vec<IndexPart> = column_refs(&block.body, "columns", &context)
+        .unwrap_or_default()
+        .into_iter()
+        .map(IndexPart::Column)
+        .collect();
+    for on in block.body.blocks().filter(|b| b.identifier() == "on") {
+        match (attribute(&on.body, "column"), attribute(&on.body, "expr")) {
+            (Some(_), _) => {
+                parts.push(IndexPart::Column(column_ref(&on.body, "column", &context)?))
+            }
+            (None, Some(Expression::String(expr))) => parts.push(IndexPart::Expr(expr.clone())),
+            _ => {
+                return Err(AtlasError::invalid(
+                    &context,
+                    "`on` needs a `column` or an `expr`",
+                ))
+            }
+        }
+    }
+    if parts.is_empty() {
+        return Err(AtlasError::invalid(&context, "missing `columns` or `on`"));
+    }
+
+    Ok(IndexDescriptor {
+        name,
+        unique,
+        method,
+        parts,
+        predicate,
+    })
+}
+
+fn label(block: &Block) -> Result<&str, AtlasError> {
+    match block.labels() {
+        [label] => Ok(label.as_str()),
+        _ => Err(AtlasError::invalid(
+            block.identifier(),
+            "expected a single label",
+        )),
+    }
+}
+
+fn attribute<'a>(body: &'a Body, key: &str) -> Option<&'a Expression> {
+    body.attributes()
+        .find(|attr| attr.key() == key)
+        .map(|attr| attr.expr())
+}
+
+/// `schema = schema.public`, defaulting to `schema.public` when missing.
+fn schema_ref(body: &Body, context: &str) -> Result<String, AtlasError> {
+    match attribute(body, "schema") {
+        Some(expr) => match expr_path(expr) {
+            Some(path) if path.len() == 2 && path[0] == "schema" => Ok(path.join(".")),
+            _ => Err(AtlasError::invalid(
+                context,
+                "`schema` must be a `schema.<name>` reference",
+            )),
+        },
+        None => Ok("schema.public".to_string()),
+    }
+}
+
+/// The names of the `column.<name>` references of an array attribute.
+fn column_refs(body: &Body, key: &str, context: &str) -> Result<Vec<String>, AtlasError> {
+    refs(body, key, context)?
+        .into_iter()
+        .map(|path| match path.as_slice() {
+            [c, column] if c == "column" => Ok(column.clone()),
+            _ => Err(AtlasError::invalid(
+                context,
+                format!("invalid column reference `{}`", path.join(".")),
+            )),
+        })
+        .collect()
+}
+
+fn column_ref(body: &Body, key: &str, context: &str) -> Result<String, AtlasError> {
+    match attribute(body, key).and_then(expr_path).as_deref() {
+        Some([c, column]) if c == "column" => Ok(column.clone()),
+        _ => Err(AtlasError::invalid(
+            context,
+            format!("`{}` must be a column reference", key),
+        )),
+    }
+}
+
+/// The references of an array attribute, each split into its parts.
+fn refs(body: &Body, key: &str, context: &str) -> Result<Vec<Vec<String>>, AtlasError> {
+    match attribute(body, key) {
+        Some(Expression::Array(exprs)) => exprs
+            .iter()
+            .map(|expr| {
+                expr_path(expr).ok_or_else(|| {
+                    AtlasError::invalid(context, format!("`{}` must only hold references", key))
+                })
+            })
+            .collect(),
+        _ => Err(AtlasError::invalid(context, format!("missing `{}`", key))),
+    }
+}
+
+/// The parts of a reference such as `table.users.column.id`, or of a bare name like `CASCADE`.
+fn expr_path(expr: &Expression) -> Option<Vec<String>> {
+    match expr {
+        Expression::Variable(v) => Some(vec![v.to_string()]),
+        Expression::Traversal(t) => {
+            let mut path = expr_path(&t.expr)?;
+            for op in &t.operators {
+                match op {
+                    TraversalOperator::GetAttr(attr) => path.push(attr.to_string()),
+                    _ => return None,
+                }
+            }
+            Some(path)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,443 @@
+//! Generates the Rust source of entities from their descriptors, e.g. ones read from Atlas HCL
+//! with [`Schema::from_atlas_hcl`], so an existing database can be adopted without writing every
+//! struct by hand.
+
+use crate::descriptor::{
+    ColumnDefault, EdgeDescriptor, EntityDescriptor, EnumDescriptor, IndexDescriptor, IndexPart,
+};
+use crate::schema::Schema;
+use crate::sql::postgres::{ddl, ColumnType};
+
+const DEFAULT_SCHEMA: &str = "schema.public";
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Converts a Rust identifier such as `InProgress` into `in_progress`.
+pub fn to_snake_case(ident: &str) -> String {
+    let mut out = String::with_capacity(ident.len() + 4);
+    let chars: Vec<char> = ident.chars().collect();
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev_lower = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_numeric());
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let prev_upper = i > 0 && chars[i - 1].is_uppercase();
+            if prev_lower || (prev_upper && next_lower) {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(*c);
+        }
+    }
+
+    out
+}
+
+/// Converts a SQL name such as `order_status` or `in-progress` into `OrderStatus`/`InProgress`.
+pub fn to_pascal_case(name: &str) -> String {
+    let pascal: String = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    match pascal.chars().next() {
+        Some(c) if c.is_alphabetic() => pascal,
+        _ => format!("V{}", pascal),
+    }
+}
+
+/// The entity name of a table, `User` for `users` and `Category` for `categories`.
+pub fn entity_name(table_name: &str) -> String {
+    let singular = if let Some(stem) = table_name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if ["sses", "xes", "ches", "shes"]
+        .iter()
+        .any(|suffix| table_name.ends_with(suffix))
+    {
+        table_name[..table_name.len() - 2].to_string()
+    } else if table_name.ends_with('s') && !["ss", "us"].iter().any(|s| table_name.ends_with(s)) {
+        table_name[..table_name.len() - 1].to_string()
+    } else {
+        table_name.to_string()
+    };
+
+    to_pascal_case(&singular)
+}
+
+/// The Rust type of a non-null column of type `typ`, and whether `#[derive(Entity)]` maps that
+/// type back to `typ`. When it doesn't, the field needs `sql_type`. Types without a natural Rust
+/// counterpart are kept as `String`.
+pub fn rust_type(typ: &ColumnType) -> (String, bool) {
+    let (rust, exact) = match typ {
+        ColumnType::Text => ("String", true),
+        ColumnType::Char(Some(1)) => ("char", true),
+        ColumnType::Boolean => ("bool", true),
+        ColumnType::SmallInt => ("i16", true),
+        ColumnType::Integer => ("i32", true),
+        ColumnType::BigInt => ("i64", true),
+        ColumnType::Int | ColumnType::Serial => ("i32", false),
+        ColumnType::SmallSerial => ("i16", false),
+        ColumnType::BigSerial => ("i64", false),
+        ColumnType::Real => ("f32", true),
+        ColumnType::DoublePrecision => ("f64", true),
+        ColumnType::Float(_) => ("f64", false),
+        ColumnType::ByteA => ("Vec<u8>", true),
+        ColumnType::Uuid => ("uuid::Uuid", true),
+        ColumnType::TimestampTz => ("time::OffsetDateTime", true),
+        ColumnType::Timestamp(precision) => ("time::PrimitiveDateTime", precision.is_none()),
+        ColumnType::Date => ("time::Date", true),
+        ColumnType::Time => ("time::Time", true),
+        ColumnType::Jsonb => ("serde_json::Value", true),
+        ColumnType::Json => ("serde_json::Value", false),
+        ColumnType::Numeric(args) => ("rust_decimal::Decimal", args.is_none()),
+        ColumnType::INet => ("std::net::IpAddr", true),
+        ColumnType::Cidr => ("ipnetwork::IpNetwork", true),
+        ColumnType::Enum(name) => return (to_pascal_case(name), true),
+        ColumnType::Array(of) => {
+            let (rust, exact) = rust_type(of);
+            return (format!("Vec<{}>", rust), exact);
+        }
+        _ => ("String", false),
+    };
+
+    (rust.to_string(), exact)
+}
+
+/// The source of every enum and entity of the schema, enums first.
+pub fn rust_source(schema: &Schema) -> String {
+    let mut imports = vec!["Entity"];
+    if schema.enums().next().is_some() {
+        imports.push("SqlEnum");
+    }
+
+    let items = schema
+        .enums()
+        .map(enum_source)
+        .chain(schema.tables().map(entity_source));
+
+    std::iter::once(format!("use graphix::{{{}}};\n", imports.join(", ")))
+        .chain(items)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A `#[derive(SqlEnum)]` enum named after the postgres enum.
+pub fn enum_source(desc: &EnumDescriptor) -> String {
+    let name = to_pascal_case(&desc.name);
+
+    let mut attrs = Vec::new();
+    if to_snake_case(&name) != desc.name {
+        attrs.push(format!("name = {:?}", desc.name));
+    }
+    if desc.schema_name != DEFAULT_SCHEMA {
+        attrs.push(format!("schema_name = {:?}", desc.schema_name));
+    }
+
+    let mut out = String::from("#[derive(SqlEnum)]\n");
+    out.push_str(&graphix_attr("", &attrs));
+    out.push_str(&format!("pub enum {} {{\n", name));
+    for value in &desc.values {
+        let variant = to_pascal_case(value);
+        if to_snake_case(&variant) != *value {
+            out.push_str(&format!("    #[graphix(rename = {:?})]\n", value));
+        }
+        out.push_str(&format!("    {},\n", variant));
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+/// A `#[derive(Entity)]` struct describing the same table as `desc`. Field level `unique`
+/// covers single column unique indexes and edge level `unique` the ones on a foreign key, as
+/// long as they have the names the derive would give them.
+pub fn entity_source(desc: &EntityDescriptor) -> String {
+    let table = &desc.table_name;
+    let mut attrs = Vec::new();
+
+    if format!("{}s", desc.name.to_lowercase()) != *table {
+        attrs.push(format!("table_name = {:?}", table));
+    }
+    if desc.schema_name != DEFAULT_SCHEMA {
+        attrs.push(format!("schema_name = {:?}", desc.schema_name));
+    }
+    match desc.primary_key.as_slice() {
+        [] => attrs.push("no_primary_key".to_string()),
+        [_] => {}
+        keys => attrs.push(format!("primary_key = {}", str_array(keys))),
+    }
+
+    let foreign_keys: Vec<&EdgeDescriptor> = desc.foreign_keys().collect();
+    let mut unique_edges = Vec::new();
+
+    for index in &desc.indexes {
+        let columns = plain_columns(index);
+        let implied = index.unique
+            && index.method.is_none()
+            && index.predicate.is_none()
+            && columns
+                .as_ref()
+                .is_some_and(|cols| index.name == default_index_name(table, cols, true));
+
+        match columns {
+            // declared with `unique` on the field
+            Some(cols) if implied && cols.len() == 1 => {}
+            Some(cols) if implied && foreign_keys.iter().any(|e| e.columns == cols) => {
+                unique_edges.push(cols)
+            }
+            _ => attrs.push(index_attr(table, index)),
+        }
+    }
+
+    for edge in &foreign_keys {
+        let unique = unique_edges.contains(&edge.columns)
+            || (edge.columns.len() == 1
+                && desc
+                    .fields
+                    .iter()
+                    .any(|f| f.unique && f.column_name == edge.columns[0]));
+        attrs.push(edge_attr(edge, unique));
+    }
+
+    let mut out = String::from("#[derive(Entity)]\n");
+    out.push_str(&graphix_attr("", &attrs));
+    out.push_str(&format!("pub struct {} {{\n", desc.name));
+
+    for field in &desc.fields {
+        let ident = &field.name;
+        let (rust, exact) = rust_type(&ColumnType::from(&field.sql_type));
+
+        let mut attrs = Vec::new();
+        if desc.primary_key.len() == 1 && desc.primary_key[0] == field.column_name {
+            attrs.push("primary_key".to_string());
+        }
+        if *ident != field.column_name {
+            attrs.push(format!("colname = {:?}", field.column_name));
+        }
+        if !exact {
+            attrs.push(format!("sql_type = {:?}", field.sql_type.to_string()));
+        }
+        if field.unique {
+            attrs.push("unique".to_string());
+        }
+        if field.immutable {
+            attrs.push("immutable".to_string());
+        }
+        if let Some(default) = &field.default {
+            attrs.push(default_attr(&ColumnType::from(&field.sql_type), default));
+        }
+
+        let rust = match field.nullable {
+            true => format!("Option<{}>", rust),
+            false => rust,
+        };
+
+        out.push_str(&graphix_attr("    ", &attrs));
+        out.push_str(&format!("    pub {}: {},\n", ident, rust));
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+/// `#[graphix(...)]`, on one line when it fits and one argument per line otherwise.
+fn graphix_attr(indent: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        return String::new();
+    }
+
+    let line = format!("{}#[graphix({})]\n", indent, args.join(", "));
+    if line.len() <= 100 {
+        return line;
+    }
+
+    let args: String = args
+        .iter()
+        .map(|arg| format!("{}    {},\n", indent, arg))
+        .collect();
+    format!("{0}#[graphix(\n{1}{0})]\n", indent, args)
+}
+
+fn index_attr(table: &str, index: &IndexDescriptor) -> String {
+    let mut args = Vec::new();
+    match plain_columns(index) {
+        Some(columns) => args.push(format!("columns = {}", str_array(&columns))),
+        None => {
+            let parts: Vec<String> = index
+                .parts
+                .iter()
+                .map(|part| match part {
+                    IndexPart::Column(col) => format!("{:?}", col),
+                    IndexPart::Expr(expr) => format!("expr({:?})", expr),
+                })
+                .collect();
+            args.push(format!("parts = [{}]", parts.join(", ")));
+        }
+    }
+    if index.unique {
+        args.push("unique".to_string());
+    }
+    if let Some(method) = index.method {
+        args.push(format!("method = \"{}\"", method));
+    }
+    if let Some(predicate) = &index.predicate {
+        args.push(format!("predicate = {:?}", predicate));
+    }
+
+    // the derive only names plain column indexes itself
+    let named = match plain_columns(index) {
+        Some(cols) => index.name != default_index_name(table, &cols, index.unique),
+        None => true,
+    };
+    if named {
+        args.push(format!("name = {:?}", index.name));
+    }
+
+    format!("index({})", args.join(", "))
+}
+
+fn edge_attr(edge: &EdgeDescriptor, unique: bool) -> String {
+    let mut args = vec![format!("to = {}", edge.target)];
+    match edge.columns.as_slice() {
+        [col] => args.push(format!("field = {:?}", col)),
+        cols => args.push(format!("fields = {}", str_array(cols))),
+    }
+    if unique {
+        args.push("unique".to_string());
+    }
+    if let Some(action) = edge.on_delete {
+        args.push(format!(
+            "on_delete = {:?}",
+            action.as_atlas_str().to_lowercase()
+        ));
+    }
+    if let Some(action) = edge.on_update {
+        args.push(format!(
+            "on_update = {:?}",
+            action.as_atlas_str().to_lowercase()
+        ));
+    }
+
+    format!("edge({})", args.join(", "))
+}
+
+/// `default = ...` for literals the column accepts, `default_expr = "..."` for everything else.
+fn default_attr(typ: &ColumnType, default: &ColumnDefault) -> String {
+    if !typ.accepts_default(default) {
+        return format!("default_expr = {:?}", ddl::default_sql(default));
+    }
+
+    match default {
+        ColumnDefault::Bool(b) => format!("default = {}", b),
+        ColumnDefault::Int(i) => format!("default = {}", i),
+        ColumnDefault::Float(f) if f.contains(['.', 'e', 'E']) => format!("default = {}", f),
+        ColumnDefault::Float(f) => format!("default = {}.0", f),
+        ColumnDefault::Str(s) => format!("default = {:?}", s),
+        ColumnDefault::Expr(e) => format!("default_expr = {:?}", e),
+    }
+}
+
+/// The columns of an index without expressions.
+fn plain_columns(index: &IndexDescriptor) -> Option<Vec<String>> {
+    index
+        .parts
+        .iter()
+        .map(|part| match part {
+            IndexPart::Column(col) => Some(col.clone()),
+            IndexPart::Expr(_) => None,
+        })
+        .collect()
+}
+
+/// The name `#[derive(Entity)]` gives an index on `columns` that isn't given a `name`.
+fn default_index_name(table: &str, columns: &[String], unique: bool) -> String {
+    format!(
+        "idx_{}_{}{}",
+        table,
+        columns.join("_"),
+        if unique { "_unique" } else { "" }
+    )
+}
+
+/// A field name for a column, with `colname` covering any difference.
+pub fn field_ident(column_name: &str) -> String {
+    let mut ident: String = to_snake_case(column_name)
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_numeric()) {
+        ident.insert(0, '_');
+    }
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+
+    ident
+}
+
+fn str_array<S: AsRef<str>>(values: &[S]) -> String {
+    let values: Vec<String> = values.iter().map(|v| format!("{:?}", v.as_ref())).collect();
+    format!("[{}]", values.join(", "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(to_snake_case("InProgress"), "in_progress");
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_pascal_case("order_status"), "OrderStatus");
+        assert_eq!(to_pascal_case("in-progress"), "InProgress");
+        assert_eq!(to_pascal_case("2fa"), "V2fa");
+        assert_eq!(entity_name("users"), "User");
+        assert_eq!(entity_name("categories"), "Category");
+        assert_eq!(entity_name("addresses"), "Address");
+        assert_eq!(entity_name("user_groups"), "UserGroup");
+        assert_eq!(entity_name("status"), "Status");
+        assert_eq!(field_ident("type"), "type_");
+        assert_eq!(field_ident("createdAt"), "created_at");
+        assert_eq!(field_ident("2nd"), "_2nd");
+    }
+
+    #[test]
+    fn test_rust_type() {
+        assert_eq!(rust_type(&ColumnType::BigInt), ("i64".to_string(), true));
+        assert_eq!(
+            rust_type(&ColumnType::VarChar(Some(8))),
+            ("String".to_string(), false)
+        );
+        assert_eq!(
+            rust_type(&ColumnType::Array(Box::new(ColumnType::Enum(
+                "mood".to_string()
+            )))),
+            ("Vec<Mood>".to_string(), true)
+        );
+        assert_eq!(
+            rust_type(&ColumnType::Interval),
+            ("String".to_string(), false)
+        );
+    }
+}
@@ -1,20 +1,343 @@
-use crate::sql::postgres::ColumnType;
+use crate::sql::LogicalType;
+use quote::{quote, ToTokens};
+use std::fmt::Formatter;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityDescriptor {
     pub name: String,
     pub table_name: String,
     pub schema_name: String,
     pub fields: Vec<EntityFieldDescriptor>,
+    /// Column names of the primary key, empty for entities declared with `no_primary_key`.
+    pub primary_key: Vec<String>,
+    pub indexes: Vec<IndexDescriptor>,
+    pub edges: Vec<EdgeDescriptor>,
+    pub enums: Vec<EnumDescriptor>,
+    /// Join tables of the many-to-many edges this entity owns.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub join_tables: Vec<EntityDescriptor>,
 }
+impl EntityDescriptor {
+    /// Builds the join table of a many-to-many edge from `owner` to `target`. Its columns are the
+    /// primary keys of both sides followed by `edge_fields`, its primary key is made up of both
+    /// foreign keys, and both foreign keys cascade on delete.
+    pub fn join_table(
+        table_name: &str,
+        schema_name: &str,
+        owner: JoinSide,
+        target: JoinSide,
+        edge_fields: Vec<EntityFieldDescriptor>,
+        enums: Vec<EnumDescriptor>,
+    ) -> EntityDescriptor {
+        let mut fields = Vec::new();
+        let mut primary_key = Vec::new();
+        let mut edges = Vec::new();
 
-#[derive(Debug)]
+        for side in [&owner, &target] {
+            let mut columns = Vec::new();
+            let mut ref_columns = Vec::new();
+
+            for key in &side.primary_key {
+                let column_name = format!("{}_{}", side.column_prefix, key.column_name);
+                fields.push(EntityFieldDescriptor {
+                    name: column_name.clone(),
+                    column_name: column_name.clone(),
+                    typ: key.typ.clone(),
+                    sql_type: key.sql_type.as_reference(),
+                    unique: false,
+                    immutable: true,
+                    nullable: false,
+                    default: None,
+                    unsigned: key.unsigned,
+                });
+                columns.push(column_name);
+                ref_columns.push(key.column_name.clone());
+            }
+
+            primary_key.extend(columns.iter().cloned());
+            edges.push(EdgeDescriptor {
+                name: side.column_prefix.clone(),
+                target: side.entity.clone(),
+                target_table: side.table_name.clone(),
+                target_schema: side.schema_name.clone(),
+                cardinality: EdgeCardinality::M2O,
+                inverse: false,
+                columns,
+                ref_columns,
+                on_delete: Some(ReferenceAction::Cascade),
+                on_update: None,
+                through: None,
+            });
+        }
+
+        fields.extend(edge_fields);
+
+        EntityDescriptor {
+            name: table_name.to_string(),
+            table_name: table_name.to_string(),
+            schema_name: schema_name.to_string(),
+            fields,
+            primary_key,
+            indexes: vec![],
+            edges,
+            enums,
+            join_tables: vec![],
+        }
+    }
+}
+
+/// One side of a many-to-many join table.
+#[derive(Debug, Clone)]
+pub struct JoinSide {
+    pub entity: String,
+    pub table_name: String,
+    pub schema_name: String,
+    /// Prefix of the join table's columns referencing this side, e.g. `user` for `user_id`.
+    pub column_prefix: String,
+    pub primary_key: Vec<EntityFieldDescriptor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityFieldDescriptor {
     pub name: String,
     pub column_name: String,
     pub typ: String,
-    pub sql_type: ColumnType,
+    pub sql_type: LogicalType,
     pub unique: bool,
     pub immutable: bool,
     pub nullable: bool,
+    pub default: Option<ColumnDefault>,
+    /// The unsigned Rust integer the field holds, if any. Postgres stores it in the signed
+    /// column of the same width, dialects with unsigned integers keep it unsigned.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub unsigned: Option<Unsigned>,
+}
+
+/// The width of an unsigned Rust integer, `usize` counting as `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Unsigned {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+/// The `DEFAULT` of a column, either a literal or a SQL expression evaluated by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ColumnDefault {
+    Bool(bool),
+    Int(i64),
+    /// A float literal, kept as written.
+    Float(String),
+    Str(String),
+    Expr(String),
+}
+impl ToTokens for ColumnDefault {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let path = quote! { graphix::descriptor::ColumnDefault };
+
+        tokens.extend(match self {
+            Self::Bool(v) => quote! { #path::Bool(#v) },
+            Self::Int(v) => quote! { #path::Int(#v) },
+            Self::Float(v) => quote! { #path::Float(#v.to_string()) },
+            Self::Str(v) => quote! { #path::Str(#v.to_string()) },
+            Self::Expr(v) => quote! { #path::Expr(#v.to_string()) },
+        });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumDescriptor {
+    pub name: String,
+    pub schema_name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexDescriptor {
+    pub name: String,
+    pub unique: bool,
+    /// The index method, `None` uses the database default (btree on postgres).
+    pub method: Option<IndexMethod>,
+    pub parts: Vec<IndexPart>,
+    /// The predicate of a partial index, e.g. `deleted_at IS NULL`.
+    pub predicate: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum IndexPart {
+    Column(String),
+    Expr(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum IndexMethod {
+    BTree,
+    Hash,
+    Gin,
+    Gist,
+    Brin,
+}
+impl std::fmt::Display for IndexMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BTree => write!(f, "btree"),
+            Self::Hash => write!(f, "hash"),
+            Self::Gin => write!(f, "gin"),
+            Self::Gist => write!(f, "gist"),
+            Self::Brin => write!(f, "brin"),
+        }
+    }
+}
+impl std::str::FromStr for IndexMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "btree" => Ok(Self::BTree),
+            "hash" => Ok(Self::Hash),
+            "gin" => Ok(Self::Gin),
+            "gist" => Ok(Self::Gist),
+            "brin" => Ok(Self::Brin),
+            _ => Err(format!(
+                "unknown index method `{}`, expected one of btree, hash, gin, gist or brin",
+                s
+            )),
+        }
+    }
+}
+impl ToTokens for IndexMethod {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let path = quote! { graphix::descriptor::IndexMethod };
+
+        tokens.extend(match self {
+            Self::BTree => quote! { #path::BTree },
+            Self::Hash => quote! { #path::Hash },
+            Self::Gin => quote! { #path::Gin },
+            Self::Gist => quote! { #path::Gist },
+            Self::Brin => quote! { #path::Brin },
+        });
+    }
+}
+
+/// A relation between two entities. The entity declaring an edge with `to` owns the foreign
+/// key, the inverse edge declared with `from` only records the relation. Many-to-many edges have
+/// their foreign keys in the join table named by `through`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeDescriptor {
+    pub name: String,
+    /// Name of the entity on the other end of the edge.
+    pub target: String,
+    pub target_table: String,
+    pub target_schema: String,
+    pub cardinality: EdgeCardinality,
+    pub inverse: bool,
+    /// Foreign key columns, on this table for an edge and on the target's table for an inverse
+    /// edge. For many-to-many edges, the join table columns that reference the target.
+    pub columns: Vec<String>,
+    /// Columns the foreign key references, the primary key of the referenced table.
+    pub ref_columns: Vec<String>,
+    pub on_delete: Option<ReferenceAction>,
+    pub on_update: Option<ReferenceAction>,
+    /// The join table of a many-to-many edge.
+    pub through: Option<String>,
+}
+impl EdgeDescriptor {
+    /// The name of the foreign key constraint backing a non-inverse edge.
+    pub fn foreign_key_name(&self, table_name: &str) -> String {
+        format!("{}_{}_fkey", table_name, self.columns.join("_"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeCardinality {
+    O2O,
+    O2M,
+    M2O,
+    M2M,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ReferenceAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+impl ReferenceAction {
+    /// The spelling Atlas HCL uses, e.g. `SET_NULL`.
+    pub fn as_atlas_str(&self) -> &'static str {
+        match self {
+            Self::NoAction => "NO_ACTION",
+            Self::Restrict => "RESTRICT",
+            Self::Cascade => "CASCADE",
+            Self::SetNull => "SET_NULL",
+            Self::SetDefault => "SET_DEFAULT",
+        }
+    }
+}
+impl std::fmt::Display for ReferenceAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoAction => write!(f, "NO ACTION"),
+            Self::Restrict => write!(f, "RESTRICT"),
+            Self::Cascade => write!(f, "CASCADE"),
+            Self::SetNull => write!(f, "SET NULL"),
+            Self::SetDefault => write!(f, "SET DEFAULT"),
+        }
+    }
+}
+impl std::str::FromStr for ReferenceAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['_', ' '], "").as_str() {
+            "noaction" => Ok(Self::NoAction),
+            "restrict" => Ok(Self::Restrict),
+            "cascade" => Ok(Self::Cascade),
+            "setnull" => Ok(Self::SetNull),
+            "setdefault" => Ok(Self::SetDefault),
+            _ => Err(format!(
+                "unknown reference action `{}`, expected one of no_action, restrict, cascade, \
+                 set_null or set_default",
+                s
+            )),
+        }
+    }
+}
+impl ToTokens for ReferenceAction {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let path = quote! { graphix::descriptor::ReferenceAction };
+
+        tokens.extend(match self {
+            Self::NoAction => quote! { #path::NoAction },
+            Self::Restrict => quote! { #path::Restrict },
+            Self::Cascade => quote! { #path::Cascade },
+            Self::SetNull => quote! { #path::SetNull },
+            Self::SetDefault => quote! { #path::SetDefault },
+        });
+    }
 }
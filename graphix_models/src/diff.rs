@@ -0,0 +1,333 @@
+use crate::descriptor::{
+    ColumnDefault, EdgeDescriptor, EntityDescriptor, EntityFieldDescriptor, EnumDescriptor,
+    IndexDescriptor,
+};
+use crate::schema::Schema;
+use crate::sql::LogicalType;
+
+/// A single change between two schemas. Changes that act on a table carry the table's descriptor,
+/// the new one unless the table or column is being dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    AddSchema(String),
+    AddEnum(EnumDescriptor),
+    /// Moves an enum out of the way so it can be recreated, its columns are converted after.
+    RenameEnum {
+        desc: EnumDescriptor,
+        name: String,
+    },
+    /// A value appended to an existing enum.
+    AddEnumValue {
+        desc: EnumDescriptor,
+        value: String,
+    },
+    DropForeignKey {
+        table: EntityDescriptor,
+        edge: EdgeDescriptor,
+    },
+    DropIndex {
+        table: EntityDescriptor,
+        index: IndexDescriptor,
+    },
+    DropTable(EntityDescriptor),
+    AddTable(EntityDescriptor),
+    AddColumn {
+        table: EntityDescriptor,
+        field: EntityFieldDescriptor,
+    },
+    AlterColumnType {
+        table: EntityDescriptor,
+        column: String,
+        from: LogicalType,
+        to: LogicalType,
+    },
+    AlterColumnNullable {
+        table: EntityDescriptor,
+        column: String,
+        nullable: bool,
+    },
+    AlterColumnDefault {
+        table: EntityDescriptor,
+        column: String,
+        default: Option<ColumnDefault>,
+    },
+    AlterPrimaryKey {
+        table: EntityDescriptor,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    DropColumn {
+        table: EntityDescriptor,
+        column: String,
+    },
+    AddIndex {
+        table: EntityDescriptor,
+        index: IndexDescriptor,
+    },
+    AddForeignKey {
+        table: EntityDescriptor,
+        edge: EdgeDescriptor,
+    },
+    DropEnum(EnumDescriptor),
+    DropSchema(String),
+}
+
+/// The changes turning one schema into another, in an order they can be applied in: anything
+/// referencing what's being dropped goes first and anything referencing what's being added last.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDiff {
+    pub changes: Vec<Change>,
+}
+impl SchemaDiff {
+    pub fn between(from: &Schema, to: &Schema) -> Self {
+        let mut changes = Vec::new();
+
+        let from_schemas = from.schemas();
+        let to_schemas = to.schemas();
+        for name in to_schemas.difference(&from_schemas) {
+            changes.push(Change::AddSchema(name.to_string()));
+        }
+
+        // enums can only grow in place, anything else recreates them
+        let mut dropped_enums = Vec::new();
+        let mut recreated_enums = Vec::new();
+        for e in to.enums() {
+            match find_enum(from, e) {
+                None => changes.push(Change::AddEnum(e.clone())),
+                Some(old) if old.values == e.values => {}
+                Some(old) if e.values.starts_with(&old.values) => {
+                    changes.extend(e.values[old.values.len()..].iter().map(|value| {
+                        Change::AddEnumValue {
+                            desc: e.clone(),
+                            value: value.clone(),
+                        }
+                    }))
+                }
+                Some(old) => {
+                    let renamed = format!("{}_old", old.name);
+                    changes.push(Change::RenameEnum {
+                        desc: old.clone(),
+                        name: renamed.clone(),
+                    });
+                    changes.push(Change::AddEnum(e.clone()));
+                    dropped_enums.push(Change::DropEnum(EnumDescriptor {
+                        name: renamed,
+                        ..old.clone()
+                    }));
+                    recreated_enums.push(e.name.clone());
+                }
+            }
+        }
+        for e in from.enums() {
+            if find_enum(to, e).is_none() {
+                dropped_enums.push(Change::DropEnum(e.clone()));
+            }
+        }
+
+        // pairs of (old, new) tables present in both
+        let kept: Vec<(&EntityDescriptor, &EntityDescriptor)> = to
+            .tables()
+            .filter_map(|t| {
+                from.table(&t.schema_name, &t.table_name)
+                    .map(|old| (old, t))
+            })
+            .collect();
+
+        for (old, new) in &kept {
+            for edge in old.foreign_keys() {
+                let name = edge.foreign_key_name(&old.table_name);
+                match find_foreign_key(new, &name) {
+                    Some(e) if same_foreign_key(edge, e) => {}
+                    _ => changes.push(Change::DropForeignKey {
+                        table: (*new).clone(),
+                        edge: edge.clone(),
+                    }),
+                }
+            }
+
+            for index in &old.indexes {
+                if !new.indexes.contains(index) {
+                    changes.push(Change::DropIndex {
+                        table: (*new).clone(),
+                        index: index.clone(),
+                    });
+                }
+            }
+        }
+
+        for table in from.tables_by_dependency().into_iter().rev() {
+            if to.table(&table.schema_name, &table.table_name).is_none() {
+                changes.push(Change::DropTable(table.clone()));
+            }
+        }
+
+        let mut added_tables = Vec::new();
+        for table in to.tables_by_dependency() {
+            if from.table(&table.schema_name, &table.table_name).is_none() {
+                changes.push(Change::AddTable(table.clone()));
+                added_tables.push(table);
+            }
+        }
+
+        let mut dropped_columns = Vec::new();
+        for (old, new) in &kept {
+            for field in &new.fields {
+                let Some(old_field) = old
+                    .fields
+                    .iter()
+                    .find(|f| f.column_name == field.column_name)
+                else {
+                    changes.push(Change::AddColumn {
+                        table: (*new).clone(),
+                        field: field.clone(),
+                    });
+                    continue;
+                };
+
+                let recreated = recreated_enums
+                    .iter()
+                    .any(|e| field.sql_type.enum_name() == Some(e.as_str()));
+                if recreated && old_field.sql_type == field.sql_type {
+                    // the old default is of the renamed type and can't be converted with it
+                    if let Some(default) = &field.default {
+                        changes.push(Change::AlterColumnDefault {
+                            table: (*new).clone(),
+                            column: field.column_name.clone(),
+                            default: None,
+                        });
+                        changes.push(Change::AlterColumnType {
+                            table: (*new).clone(),
+                            column: field.column_name.clone(),
+                            from: old_field.sql_type.clone(),
+                            to: field.sql_type.clone(),
+                        });
+                        changes.push(Change::AlterColumnDefault {
+                            table: (*new).clone(),
+                            column: field.column_name.clone(),
+                            default: Some(default.clone()),
+                        });
+                        continue;
+                    }
+                }
+
+                if old_field.sql_type != field.sql_type || recreated {
+                    changes.push(Change::AlterColumnType {
+                        table: (*new).clone(),
+                        column: field.column_name.clone(),
+                        from: old_field.sql_type.clone(),
+                        to: field.sql_type.clone(),
+                    });
+                }
+                if old_field.nullable != field.nullable {
+                    changes.push(Change::AlterColumnNullable {
+                        table: (*new).clone(),
+                        column: field.column_name.clone(),
+                        nullable: field.nullable,
+                    });
+                }
+                if old_field.default != field.default {
+                    changes.push(Change::AlterColumnDefault {
+                        table: (*new).clone(),
+                        column: field.column_name.clone(),
+                        default: field.default.clone(),
+                    });
+                }
+            }
+
+            if old.primary_key != new.primary_key {
+                changes.push(Change::AlterPrimaryKey {
+                    table: (*new).clone(),
+                    from: old.primary_key.clone(),
+                    to: new.primary_key.clone(),
+                });
+            }
+
+            for field in &old.fields {
+                if !new
+                    .fields
+                    .iter()
+                    .any(|f| f.column_name == field.column_name)
+                {
+                    dropped_columns.push(Change::DropColumn {
+                        table: (*new).clone(),
+                        column: field.column_name.clone(),
+                    });
+                }
+            }
+        }
+        changes.extend(dropped_columns);
+
+        for (old, new) in &kept {
+            for index in &new.indexes {
+                if !old.indexes.contains(index) {
+                    changes.push(Change::AddIndex {
+                        table: (*new).clone(),
+                        index: index.clone(),
+                    });
+                }
+            }
+        }
+        for table in &added_tables {
+            for index in &table.indexes {
+                changes.push(Change::AddIndex {
+                    table: (*table).clone(),
+                    index: index.clone(),
+                });
+            }
+        }
+
+        for (old, new) in &kept {
+            for edge in new.foreign_keys() {
+                let name = edge.foreign_key_name(&new.table_name);
+                match find_foreign_key(old, &name) {
+                    Some(e) if same_foreign_key(edge, e) => {}
+                    _ => changes.push(Change::AddForeignKey {
+                        table: (*new).clone(),
+                        edge: edge.clone(),
+                    }),
+                }
+            }
+        }
+        for table in &added_tables {
+            for edge in table.foreign_keys() {
+                changes.push(Change::AddForeignKey {
+                    table: (*table).clone(),
+                    edge: edge.clone(),
+                });
+            }
+        }
+
+        changes.extend(dropped_enums);
+        for name in from_schemas.difference(&to_schemas) {
+            changes.push(Change::DropSchema(name.to_string()));
+        }
+
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+fn find_enum<'a>(schema: &'a Schema, e: &EnumDescriptor) -> Option<&'a EnumDescriptor> {
+    schema
+        .enums()
+        .find(|o| o.schema_name == e.schema_name && o.name == e.name)
+}
+
+fn find_foreign_key<'a>(table: &'a EntityDescriptor, name: &str) -> Option<&'a EdgeDescriptor> {
+    table
+        .foreign_keys()
+        .find(|e| e.foreign_key_name(&table.table_name) == name)
+}
+
+/// Whether two edges have the same constraint, regardless of their names and cardinality.
+fn same_foreign_key(a: &EdgeDescriptor, b: &EdgeDescriptor) -> bool {
+    a.columns == b.columns
+        && a.ref_columns == b.ref_columns
+        && a.target_schema == b.target_schema
+        && a.target_table == b.target_table
+        && a.on_delete == b.on_delete
+        && a.on_update == b.on_update
+}
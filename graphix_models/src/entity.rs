@@ -1,44 +1,245 @@
-use crate::descriptor::{EntityDescriptor, EntityFieldDescriptor};
-use hcl::expr::TraversalBuilder;
-use hcl::{Block, Expression, Identifier, Object, Traversal, TraversalOperator, Variable};
+use crate::descriptor::{
+    ColumnDefault, EdgeDescriptor, EntityDescriptor, EntityFieldDescriptor, EnumDescriptor,
+    IndexDescriptor, IndexPart,
+};
+use crate::sql::postgres::ColumnType;
+use hcl::expr::FuncCall;
+use hcl::{Block, Body, Expression, Identifier, Number, Traversal, TraversalOperator, Variable};
+
+/// Table level facts about an entity, available without an instance so that other entities'
+/// edges can refer to it. Implemented by `#[derive(Entity)]`.
+pub trait EntityTable {
+    const NAME: &'static str;
+    const TABLE_NAME: &'static str;
+    const SCHEMA_NAME: &'static str;
+    const PRIMARY_KEY: &'static [&'static str];
+
+    /// The fields making up the primary key, in key order.
+    fn primary_key_fields() -> Vec<EntityFieldDescriptor>;
+}
+
+/// The extra columns of a many-to-many join table, implemented by `#[derive(EdgeSchema)]`.
+/// `()` is an edge without extra columns.
+pub trait EdgeSchema {
+    fn fields() -> Vec<EntityFieldDescriptor>;
+    fn enums() -> Vec<EnumDescriptor>;
+}
+impl EdgeSchema for () {
+    fn fields() -> Vec<EntityFieldDescriptor> {
+        vec![]
+    }
+
+    fn enums() -> Vec<EnumDescriptor> {
+        vec![]
+    }
+}
 
 pub trait Entity {
+    /// The entity's descriptor, built on first use and cached for the life of the program.
+    fn descriptor() -> &'static EntityDescriptor
+    where
+        Self: Sized;
+
+    /// A copy of [`Entity::descriptor`], callable on trait objects.
     fn entity_descriptor(&self) -> EntityDescriptor;
     fn as_atlas_hcl(&self) -> Block {
-        let desc = &self.entity_descriptor();
+        self.entity_descriptor().as_atlas_hcl()
+    }
+
+    /// The table block along with the `enum` blocks its columns depend on and the join tables of
+    /// its many-to-many edges.
+    fn as_atlas_body(&self) -> Body {
+        self.entity_descriptor().as_atlas_body()
+    }
+}
+
+impl EntityDescriptor {
+    pub fn as_atlas_hcl(&self) -> Block {
+        let desc = self;
 
         let mut builder = Block::builder("table")
             .add_label(&desc.table_name)
-            .add_attribute(("schema", desc.schema_name.clone()));
+            .add_attribute(("schema", traversal(&desc.schema_name)));
 
         for field in &desc.fields {
+            let mut column = Block::builder("column")
+                .add_label(field.column_name.clone())
+                .add_attribute((
+                    "type",
+                    ColumnType::from(&field.sql_type).as_atlas_expr(&desc.schema_name, &desc.enums),
+                ))
+                .add_attribute(("null", field.nullable));
+
+            if let Some(default) = &field.default {
+                column = column.add_attribute(("default", default_atlas_expr(default)));
+            }
+
+            builder = builder.add_block(column.build());
+        }
+
+        if !desc.primary_key.is_empty() {
             builder = builder.add_block(
-                Block::builder("column")
-                    .add_label(field.column_name.clone())
-                    .add_attribute(("type", field.sql_type.to_string()))
-                    .add_attribute(("null", field.nullable))
+                Block::builder("primary_key")
+                    .add_attribute((
+                        "columns",
+                        desc.primary_key
+                            .iter()
+                            .map(|col| traversal(&format!("column.{}", col)))
+                            .collect::<Vec<_>>(),
+                    ))
                     .build(),
             );
+        }
 
-            if field.unique {
-                let table_name = desc.table_name.clone();
-                let colname = field.column_name.clone();
-                builder = builder.add_block(
-                    Block::builder("index")
-                        .add_label(format!("idx_{}_{}_unique", table_name, field.column_name.clone()))
-                        .add_attribute(("unique", true))
-                        .add_attribute((
-                            "columns",
-                            vec![Expression::Traversal(Box::new(Traversal::new(
-                                Variable::new("column").expect("failed to create variable `column`"),
-                                vec![TraversalOperator::GetAttr(Identifier::from(colname))],
-                            )))],
-                        ))
-                        .build(),
-                );
-            }
+        for edge in desc.foreign_keys() {
+            builder = builder.add_block(foreign_key_atlas_hcl(
+                &desc.table_name,
+                &desc.schema_name,
+                edge,
+            ));
+        }
+
+        for index in &desc.indexes {
+            builder = builder.add_block(index_atlas_hcl(index));
         }
 
         builder.build()
     }
+
+    pub fn as_atlas_body(&self) -> Body {
+        let mut enums: Vec<&EnumDescriptor> = Vec::new();
+        for e in self.enums.iter().chain(self.join_tables.iter().flat_map(|j| &j.enums)) {
+            if !enums.iter().any(|d| d.name == e.name) {
+                enums.push(e);
+            }
+        }
+
+        Body::builder()
+            .add_blocks(enums.into_iter().map(enum_atlas_hcl))
+            .add_block(self.as_atlas_hcl())
+            .add_blocks(self.join_tables.iter().map(|j| j.as_atlas_hcl()))
+            .build()
+    }
+
+    /// The edges backed by a foreign key on this table.
+    pub fn foreign_keys(&self) -> impl Iterator<Item = &EdgeDescriptor> {
+        self.edges
+            .iter()
+            .filter(|e| !e.inverse && e.through.is_none())
+    }
+}
+
+pub fn enum_atlas_hcl(desc: &EnumDescriptor) -> Block {
+    Block::builder("enum")
+        .add_label(&desc.name)
+        .add_attribute(("schema", traversal(&desc.schema_name)))
+        .add_attribute(("values", desc.values.clone()))
+        .build()
+}
+
+pub fn index_atlas_hcl(index: &IndexDescriptor) -> Block {
+    let mut builder = Block::builder("index").add_label(&index.name);
+
+    if index.unique {
+        builder = builder.add_attribute(("unique", true));
+    }
+    if let Some(method) = index.method {
+        builder = builder.add_attribute(("type", traversal(&method.to_string().to_uppercase())));
+    }
+    if let Some(predicate) = &index.predicate {
+        builder = builder.add_attribute(("where", predicate.clone()));
+    }
+
+    // plain column indexes use the short `columns` form, anything with an expression needs `on`
+    let columns: Option<Vec<Expression>> = index
+        .parts
+        .iter()
+        .map(|part| match part {
+            IndexPart::Column(col) => Some(traversal(&format!("column.{}", col))),
+            IndexPart::Expr(_) => None,
+        })
+        .collect();
+
+    match columns {
+        Some(columns) => builder.add_attribute(("columns", columns)).build(),
+        None => index
+            .parts
+            .iter()
+            .fold(builder, |builder, part| {
+                builder.add_block(match part {
+                    IndexPart::Column(col) => Block::builder("on")
+                        .add_attribute(("column", traversal(&format!("column.{}", col))))
+                        .build(),
+                    IndexPart::Expr(expr) => Block::builder("on")
+                        .add_attribute(("expr", expr.clone()))
+                        .build(),
+                })
+            })
+            .build(),
+    }
+}
+
+/// The `foreign_key` block of a non-inverse edge of a table in `schema_name`, references to a
+/// table in another schema name that schema.
+pub fn foreign_key_atlas_hcl(table_name: &str, schema_name: &str, edge: &EdgeDescriptor) -> Block {
+    let target = if edge.target_schema == schema_name {
+        edge.target_table.clone()
+    } else {
+        let schema = edge.target_schema.strip_prefix("schema.").unwrap_or(&edge.target_schema);
+        format!("{}.{}", schema, edge.target_table)
+    };
+
+    let mut builder = Block::builder("foreign_key")
+        .add_label(edge.foreign_key_name(table_name))
+        .add_attribute((
+            "columns",
+            edge.columns
+                .iter()
+                .map(|col| traversal(&format!("column.{}", col)))
+                .collect::<Vec<_>>(),
+        ))
+        .add_attribute((
+            "ref_columns",
+            edge.ref_columns
+                .iter()
+                .map(|col| traversal(&format!("table.{}.column.{}", target, col)))
+                .collect::<Vec<_>>(),
+        ));
+
+    if let Some(action) = edge.on_update {
+        builder = builder.add_attribute(("on_update", traversal(action.as_atlas_str())));
+    }
+    if let Some(action) = edge.on_delete {
+        builder = builder.add_attribute(("on_delete", traversal(action.as_atlas_str())));
+    }
+
+    builder.build()
+}
+
+pub(crate) fn default_atlas_expr(default: &ColumnDefault) -> Expression {
+    match default {
+        ColumnDefault::Bool(b) => Expression::Bool(*b),
+        ColumnDefault::Int(i) => Expression::Number(Number::from(*i)),
+        ColumnDefault::Float(f) => f
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Expression::Number)
+            .unwrap_or_else(|| FuncCall::builder("sql").arg(f.clone()).build().into()),
+        ColumnDefault::Str(s) => Expression::String(s.clone()),
+        ColumnDefault::Expr(e) => FuncCall::builder("sql").arg(e.clone()).build().into(),
+    }
+}
+
+/// Builds a reference such as `schema.public` or `enum.status`.
+pub(crate) fn traversal(path: &str) -> Expression {
+    let mut parts = path.split('.');
+    let root = parts.next().unwrap_or_default();
+
+    Expression::Traversal(Box::new(Traversal::new(
+        Variable::new(root).expect("references start with a valid identifier"),
+        parts
+            .map(|part| TraversalOperator::GetAttr(Identifier::from(part)))
+            .collect::<Vec<_>>(),
+    )))
 }
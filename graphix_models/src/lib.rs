@@ -1,3 +1,14 @@
 pub mod descriptor;
 pub mod sql;
-pub mod entity;
\ No newline at end of file
+pub mod entity;
+pub mod schema;
+pub mod diff;
+pub mod atlas;
+pub mod codegen;
+pub mod query;
+pub mod mutation;
+#[cfg(feature = "migrate")]
+pub mod migrate;
+
+#[doc(hidden)]
+pub use inventory;
@@ -0,0 +1,312 @@
+//! Versioned migration directories in the layout `atlas migrate` uses: `{version}_{name}.sql`
+//! files next to an `atlas.sum` file holding their checksums.
+
+use crate::diff::SchemaDiff;
+use crate::sql::postgres::ddl;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+pub const SUM_FILE: &str = "atlas.sum";
+
+#[derive(Debug)]
+pub enum MigrateError {
+    Io(io::Error),
+    /// The directory has migrations but no `atlas.sum`.
+    MissingSum,
+    InvalidSum(String),
+    /// The migrations don't match `atlas.sum`, `file` is the first one that differs when known.
+    ChecksumMismatch {
+        file: Option<String>,
+    },
+    /// A migration with the same file name but different contents already exists.
+    AlreadyExists(PathBuf),
+}
+impl std::fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::MissingSum => write!(f, "migration directory has no `{}` file", SUM_FILE),
+            Self::InvalidSum(reason) => write!(f, "invalid `{}` file: {}", SUM_FILE, reason),
+            Self::ChecksumMismatch { file: Some(file) } => write!(
+                f,
+                "checksum mismatch, `{}` was changed after `{}` was written",
+                file, SUM_FILE
+            ),
+            Self::ChecksumMismatch { file: None } => write!(f, "checksum mismatch"),
+            Self::AlreadyExists(path) => write!(
+                f,
+                "migration `{}` already exists with different contents",
+                path.display()
+            ),
+        }
+    }
+}
+impl std::error::Error for MigrateError {}
+impl From<io::Error> for MigrateError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// The contents of `atlas.sum`. Each file's hash covers the names and contents of every file up
+/// to and including it, so changing, adding or reordering migrations changes every hash after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashFile {
+    /// (file name, `h1:` less hash) in file name order.
+    pub entries: Vec<(String, String)>,
+}
+impl HashFile {
+    /// Hashes `files`, (file name, contents) pairs in the order they are applied in.
+    pub fn new<'a>(files: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> Self {
+        let mut hasher = Sha256::new();
+        let entries = files
+            .into_iter()
+            .map(|(name, contents)| {
+                hasher.update(name.as_bytes());
+                hasher.update(contents);
+                (name.to_string(), STANDARD.encode(hasher.clone().finalize()))
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// The hash of all entries, the first line of the file.
+    pub fn sum(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (name, hash) in &self.entries {
+            hasher.update(name.as_bytes());
+            hasher.update(hash.as_bytes());
+        }
+
+        STANDARD.encode(hasher.finalize())
+    }
+}
+impl std::fmt::Display for HashFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "h1:{}", self.sum())?;
+        for (name, hash) in &self.entries {
+            writeln!(f, "{} h1:{}", name, hash)?;
+        }
+
+        Ok(())
+    }
+}
+impl std::str::FromStr for HashFile {
+    type Err = MigrateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let sum = lines
+            .next()
+            .and_then(|l| l.strip_prefix("h1:"))
+            .ok_or_else(|| MigrateError::InvalidSum("missing total checksum".to_string()))?;
+
+        let entries = lines
+            .filter(|l| !l.is_empty())
+            .map(|line| {
+                line.rsplit_once(" h1:")
+                    .map(|(name, hash)| (name.to_string(), hash.to_string()))
+                    .ok_or_else(|| MigrateError::InvalidSum(format!("invalid line `{}`", line)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let file = Self { entries };
+        if file.sum() != sum {
+            return Err(MigrateError::ChecksumMismatch { file: None });
+        }
+
+        Ok(file)
+    }
+}
+
+/// A directory of versioned migrations.
+#[derive(Debug, Clone)]
+pub struct MigrationDir {
+    path: PathBuf,
+}
+impl MigrationDir {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The `.sql` files in the directory as (file name, contents), in file name order.
+    pub fn files(&self) -> Result<Vec<(String, Vec<u8>)>, MigrateError> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|e| e == "sql") {
+                let name = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                files.push((name, fs::read(&path)?));
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// The `atlas.sum` the directory's current files hash to.
+    pub fn hash_file(&self) -> Result<HashFile, MigrateError> {
+        let files = self.files()?;
+        Ok(HashFile::new(files.iter().map(|(name, contents)| {
+            (name.as_str(), contents.as_slice())
+        })))
+    }
+
+    /// Checks the migrations against `atlas.sum`, catching edited, added or removed files.
+    pub fn verify(&self) -> Result<(), MigrateError> {
+        let actual = self.hash_file()?;
+        let sum_path = self.path.join(SUM_FILE);
+        if !sum_path.exists() {
+            return match actual.entries.is_empty() {
+                true => Ok(()),
+                false => Err(MigrateError::MissingSum),
+            };
+        }
+
+        let expected: HashFile = fs::read_to_string(sum_path)?.parse()?;
+        if expected == actual {
+            return Ok(());
+        }
+
+        let file = actual
+            .entries
+            .iter()
+            .zip(&expected.entries)
+            .find(|(a, e)| a != e)
+            .map(|(a, _)| a.0.clone())
+            .or_else(|| {
+                let longest = match actual.entries.len() > expected.entries.len() {
+                    true => &actual.entries,
+                    false => &expected.entries,
+                };
+                longest
+                    .get(actual.entries.len().min(expected.entries.len()))
+                    .map(|(name, _)| name.clone())
+            });
+        Err(MigrateError::ChecksumMismatch { file })
+    }
+
+    /// Writes `diff` as migration `{version}_{name}.sql` and updates `atlas.sum`. Nothing is
+    /// written for an empty diff. The directory is verified first, and writing the same
+    /// migration again is a no-op so migrations can be regenerated.
+    pub fn write(
+        &self,
+        version: &str,
+        name: &str,
+        diff: &SchemaDiff,
+    ) -> Result<Option<PathBuf>, MigrateError> {
+        self.verify()?;
+        if diff.is_empty() {
+            return Ok(None);
+        }
+
+        let mut contents = ddl::diff_statements(diff).join("\n");
+        contents.push('\n');
+
+        let path = self.path.join(format!("{}_{}.sql", version, name));
+        if path.exists() {
+            return match fs::read(&path)? == contents.as_bytes() {
+                true => Ok(Some(path)),
+                false => Err(MigrateError::AlreadyExists(path)),
+            };
+        }
+
+        fs::create_dir_all(&self.path)?;
+        fs::write(&path, contents)?;
+        fs::write(self.path.join(SUM_FILE), self.hash_file()?.to_string())?;
+
+        Ok(Some(path))
+    }
+
+    /// [`MigrationDir::write`] versioned with the current UTC time, `YYYYMMDDhhmmss` like Atlas.
+    pub fn write_now(
+        &self,
+        name: &str,
+        diff: &SchemaDiff,
+    ) -> Result<Option<PathBuf>, MigrateError> {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.write(&version(secs), name, diff)
+    }
+}
+
+/// Formats a unix timestamp as `YYYYMMDDhhmmss`.
+fn version(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // days since the epoch to a civil date, from Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        assert_eq!(version(0), "19700101000000");
+        assert_eq!(version(951_782_400), "20000229000000");
+        assert_eq!(version(1_718_022_896), "20240610123456");
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let file = HashFile::new([
+            ("1_init.sql", "CREATE TABLE t (id int);\n".as_bytes()),
+            ("2_more.sql", "DROP TABLE t;\n".as_bytes()),
+        ]);
+
+        // hashes are cumulative, the second depends on the first file
+        let alone = HashFile::new([("2_more.sql", "DROP TABLE t;\n".as_bytes())]);
+        assert_ne!(file.entries[1].1, alone.entries[0].1);
+
+        let text = file.to_string();
+        assert!(text.starts_with(&format!("h1:{}\n1_init.sql h1:", file.sum())));
+        assert_eq!(text.parse::<HashFile>().unwrap(), file);
+
+        let tampered = text.replace("2_more.sql", "2_less.sql");
+        assert!(matches!(
+            tampered.parse::<HashFile>(),
+            Err(MigrateError::ChecksumMismatch { .. })
+        ));
+    }
+}
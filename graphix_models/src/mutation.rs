@@ -0,0 +1,294 @@
+//! Inserts, updates and deletes rendered as parameterised postgres SQL. `#[derive(Entity)]`
+//! wraps these in builders with a setter per field, leaving out the setters of immutable fields
+//! on updates. Their columns are [`Immutable`], so an [`Update`] can't set them either:
+//! ```ignore
+//! let (sql, params) = User::create().set_name("x").set_age(30).to_sql()?;
+//! let (sql, params) = User::update_one(1).set_name("y").to_sql()?;
+//! let (sql, params) = User::update().filter(UserColumns::age().lt(18)).clear_nickname().to_sql()?;
+//! let (sql, params) = User::delete().filter(UserColumns::name().eq("y")).to_sql()?;
+//! ```
+//! Updates and deletes without a filter are an error unless they opt in to every row with
+//! `all()`.
+
+use crate::entity::Entity;
+use crate::query::{column_list, Column, IntoValue, Predicate, Value};
+use crate::sql::postgres::ddl::{qualified, quote_ident};
+use std::fmt::Formatter;
+
+/// Why a mutation can't be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MutationError {
+    /// A create leaves out a field the database has no value for.
+    MissingField {
+        entity: &'static str,
+        field: &'static str,
+    },
+    /// An update doesn't set anything.
+    NoChanges { entity: &'static str },
+    /// An update or delete has no filter and didn't opt in to every row with `all()`.
+    NoFilter { entity: &'static str },
+}
+impl std::fmt::Display for MutationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField { entity, field } => {
+                write!(f, "creating `{}` requires a value for `{}`", entity, field)
+            }
+            Self::NoChanges { entity } => {
+                write!(f, "update of `{}` doesn't set any field", entity)
+            }
+            Self::NoFilter { entity } => write!(
+                f,
+                "mutation of `{}` has no filter, use `all()` to affect every row",
+                entity
+            ),
+        }
+    }
+}
+impl std::error::Error for MutationError {}
+
+/// Marks the [`Column`] of a field that can be changed after the row is created.
+pub struct Mutable;
+
+/// Marks the [`Column`] of an `immutable` field, which can only be set on create.
+pub struct Immutable;
+
+/// An `INSERT` of one row of entity `E`, returning the inserted row. Fields left out get their
+/// default, nullable ones `NULL`.
+pub struct Create<E> {
+    values: Vec<(&'static str, Value)>,
+    _entity: std::marker::PhantomData<fn() -> E>,
+}
+impl<E: Entity> Default for Create<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> Create<E> {
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            _entity: std::marker::PhantomData,
+        }
+    }
+
+    pub fn set<T, M, K>(mut self, column: Column<E, T, M, K>, value: impl IntoValue<T>) -> Self {
+        set_value(&mut self.values, column, value.into_value());
+        self
+    }
+
+    /// The statement with `$n` placeholders and the parameters to bind to them. Every field
+    /// that isn't nullable, serial or defaulted has to be set.
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>), MutationError> {
+        let desc = E::descriptor();
+
+        let missing = desc.fields.iter().find(|f| {
+            !f.nullable
+                && f.default.is_none()
+                && !f.sql_type.is_serial()
+                && !self.values.iter().any(|(col, _)| *col == f.column_name)
+        });
+        if let Some(field) = missing {
+            return Err(MutationError::MissingField {
+                entity: &desc.name,
+                field: &field.name,
+            });
+        }
+
+        let table = qualified(&desc.schema_name, &desc.table_name);
+        let sql = if self.values.is_empty() {
+            format!(
+                "INSERT INTO {} DEFAULT VALUES RETURNING {}",
+                table,
+                column_list(desc)
+            )
+        } else {
+            let columns = self
+                .values
+                .iter()
+                .map(|(col, _)| quote_ident(col))
+                .collect::<Vec<_>>();
+            let placeholders = (1..=self.values.len())
+                .map(|i| format!("${}", i))
+                .collect::<Vec<_>>();
+
+            format!(
+                "INSERT INTO {} ({}) VALUES ({}) RETURNING {}",
+                table,
+                columns.join(", "),
+                placeholders.join(", "),
+                column_list(desc)
+            )
+        };
+
+        Ok((sql, self.values.iter().map(|(_, v)| v.clone()).collect()))
+    }
+}
+
+/// An `UPDATE` of the rows of entity `E` matching its filters, or of every row after `all()`.
+pub struct Update<E> {
+    values: Vec<(&'static str, Value)>,
+    predicate: Option<Predicate<E>>,
+    all: bool,
+    returning: bool,
+}
+impl<E: Entity> Default for Update<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> Update<E> {
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            predicate: None,
+            all: false,
+            returning: false,
+        }
+    }
+
+    /// An update of the single row matching `predicate`, usually its primary key, returning the
+    /// updated row.
+    pub fn one(predicate: Predicate<E>) -> Self {
+        Self {
+            values: Vec::new(),
+            predicate: Some(predicate),
+            all: false,
+            returning: true,
+        }
+    }
+
+    /// Sets `column` to `value`, the columns of immutable fields can't be passed.
+    pub fn set<T, K>(mut self, column: Column<E, T, Mutable, K>, value: impl IntoValue<T>) -> Self {
+        set_value(&mut self.values, column, value.into_value());
+        self
+    }
+
+    /// Sets a nullable `column` to `NULL`.
+    pub fn clear<T, K>(mut self, column: Column<E, Option<T>, Mutable, K>) -> Self {
+        set_value(&mut self.values, column, Value::Null);
+        self
+    }
+
+    pub fn filter(mut self, predicate: Predicate<E>) -> Self {
+        self.predicate = Some(match self.predicate {
+            Some(p) => p.and(predicate),
+            None => predicate,
+        });
+        self
+    }
+
+    /// Updates every row when there are no filters, which is otherwise an error.
+    pub fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    /// The statement with `$n` placeholders and the parameters to bind to them, the values set
+    /// come before the ones of the filters.
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>), MutationError> {
+        let desc = E::descriptor();
+        if self.values.is_empty() {
+            return Err(MutationError::NoChanges { entity: &desc.name });
+        }
+        if self.predicate.is_none() && !self.all {
+            return Err(MutationError::NoFilter { entity: &desc.name });
+        }
+
+        let mut params = Vec::new();
+        let sets = self
+            .values
+            .iter()
+            .map(|(col, value)| {
+                params.push(value.clone());
+                format!("{} = ${}", quote_ident(col), params.len())
+            })
+            .collect::<Vec<_>>();
+
+        let mut sql = format!(
+            "UPDATE {} SET {}",
+            qualified(&desc.schema_name, &desc.table_name),
+            sets.join(", ")
+        );
+        if let Some(predicate) = &self.predicate {
+            sql.push_str(" WHERE ");
+            sql.push_str(&predicate.to_sql(&mut params));
+        }
+        if self.returning {
+            sql.push_str(" RETURNING ");
+            sql.push_str(&column_list(desc));
+        }
+
+        Ok((sql, params))
+    }
+}
+
+/// A `DELETE` of the rows of entity `E` matching its filters, or of every row after `all()`.
+pub struct Delete<E> {
+    predicate: Option<Predicate<E>>,
+    all: bool,
+}
+impl<E: Entity> Default for Delete<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> Delete<E> {
+    pub fn new() -> Self {
+        Self {
+            predicate: None,
+            all: false,
+        }
+    }
+
+    pub fn filter(mut self, predicate: Predicate<E>) -> Self {
+        self.predicate = Some(match self.predicate {
+            Some(p) => p.and(predicate),
+            None => predicate,
+        });
+        self
+    }
+
+    /// Deletes every row when there are no filters, which is otherwise an error.
+    pub fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    /// The statement with `$n` placeholders and the parameters to bind to them.
+    pub fn to_sql(&self) -> Result<(String, Vec<Value>), MutationError> {
+        let desc = E::descriptor();
+        if self.predicate.is_none() && !self.all {
+            return Err(MutationError::NoFilter { entity: &desc.name });
+        }
+
+        let mut params = Vec::new();
+
+        let mut sql = format!(
+            "DELETE FROM {}",
+            qualified(&desc.schema_name, &desc.table_name)
+        );
+        if let Some(predicate) = &self.predicate {
+            sql.push_str(" WHERE ");
+            sql.push_str(&predicate.to_sql(&mut params));
+        }
+
+        Ok((sql, params))
+    }
+}
+
+/// Sets the value of `column`, replacing an earlier one.
+fn set_value<E, T, M, K>(
+    values: &mut Vec<(&'static str, Value)>,
+    column: Column<E, T, M, K>,
+    value: Value,
+) {
+    let col = column.field().column_name.as_str();
+    match values.iter_mut().find(|(c, _)| *c == col) {
+        Some((_, v)) => *v = value,
+        None => values.push((col, value)),
+    }
+}
@@ -0,0 +1,475 @@
+//! Typed queries rendered as parameterised postgres SQL. `#[derive(Entity)]` generates a
+//! `<Name>Columns` type with a [`Column`] for every field, and a `query()` to start from:
+//! ```ignore
+//! let (sql, params) = User::query()
+//!     .filter(UserColumns::name().eq("x"))
+//!     .order_by(UserColumns::created_at().desc())
+//!     .limit(10)
+//!     .to_sql();
+//! ```
+//! Which predicates a column has depends on the Rust type of its field, or on its `sql_type` when it
+//! has one, so comparing a number with a string or calling `is_null` on a required field doesn't
+//! compile.
+
+mod value;
+
+pub use value::{kind, ColumnValue, IntoParam, IntoValue, OrderedColumn, TextColumn, Value};
+
+use crate::descriptor::{EntityDescriptor, EntityFieldDescriptor};
+use crate::entity::Entity;
+use crate::mutation::Mutable;
+use crate::sql::postgres::ddl::{qualified, quote_ident};
+use std::marker::PhantomData;
+
+/// A column of entity `E` whose field is a `T`. `M` is [`Mutable`] unless the field is immutable,
+/// only mutable columns can be set by an update. The predicates of the column follow `K`, the
+/// field's type or one of the [`kind`]s when its `sql_type` is declared.
+pub struct Column<E, T, M = Mutable, K = T> {
+    field: &'static EntityFieldDescriptor,
+    _marker: PhantomData<fn() -> (E, T)>,
+    _mutability: PhantomData<(M, fn() -> K)>,
+}
+impl<E, T, M, K> Clone for Column<E, T, M, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<E, T, M, K> Copy for Column<E, T, M, K> {}
+
+impl<E, T, M, K> Column<E, T, M, K> {
+    /// The column described by `field`, which has to be one of `E`'s fields of type `T`, and
+    /// immutable if `M` is [`crate::mutation::Immutable`].
+    pub fn new(field: &'static EntityFieldDescriptor) -> Self {
+        Self {
+            field,
+            _marker: PhantomData,
+            _mutability: PhantomData,
+        }
+    }
+
+    pub fn field(&self) -> &'static EntityFieldDescriptor {
+        self.field
+    }
+
+    pub fn asc(self) -> Order<E> {
+        Order::new(&self.field.column_name, false)
+    }
+
+    pub fn desc(self) -> Order<E> {
+        Order::new(&self.field.column_name, true)
+    }
+
+    fn like(self, pattern: String, case_insensitive: bool) -> Predicate<E> {
+        Predicate::new(Expr::Like {
+            column: &self.field.column_name,
+            pattern,
+            case_insensitive,
+        })
+    }
+}
+
+impl<E, T, M, K> Column<E, T, M, K> {
+    pub fn eq(self, value: impl IntoValue<T>) -> Predicate<E> {
+        self.compare("=", value)
+    }
+
+    pub fn ne(self, value: impl IntoValue<T>) -> Predicate<E> {
+        self.compare("<>", value)
+    }
+
+    /// Whether the column is one of `values`, never true for no values.
+    pub fn is_in<V: IntoValue<T>>(self, values: impl IntoIterator<Item = V>) -> Predicate<E> {
+        self.one_of(values, false)
+    }
+
+    /// Whether the column is none of `values`, always true for no values.
+    pub fn not_in<V: IntoValue<T>>(self, values: impl IntoIterator<Item = V>) -> Predicate<E> {
+        self.one_of(values, true)
+    }
+
+    fn compare(self, op: &'static str, value: impl IntoValue<T>) -> Predicate<E> {
+        Predicate::new(Expr::Compare {
+            column: &self.field.column_name,
+            op,
+            value: value.into_value(),
+        })
+    }
+
+    fn one_of<V: IntoValue<T>>(
+        self,
+        values: impl IntoIterator<Item = V>,
+        negated: bool,
+    ) -> Predicate<E> {
+        Predicate::new(Expr::In {
+            column: &self.field.column_name,
+            values: values.into_iter().map(IntoValue::into_value).collect(),
+            negated,
+        })
+    }
+}
+
+impl<E, T, M, K: OrderedColumn> Column<E, T, M, K> {
+    pub fn gt(self, value: impl IntoValue<T>) -> Predicate<E> {
+        self.compare(">", value)
+    }
+
+    pub fn gte(self, value: impl IntoValue<T>) -> Predicate<E> {
+        self.compare(">=", value)
+    }
+
+    pub fn lt(self, value: impl IntoValue<T>) -> Predicate<E> {
+        self.compare("<", value)
+    }
+
+    pub fn lte(self, value: impl IntoValue<T>) -> Predicate<E> {
+        self.compare("<=", value)
+    }
+}
+
+impl<E, T, M, K: TextColumn> Column<E, T, M, K> {
+    pub fn contains(self, s: &str) -> Predicate<E> {
+        self.like(format!("%{}%", escape_like(s)), false)
+    }
+
+    /// [`Column::contains`] ignoring case.
+    pub fn contains_fold(self, s: &str) -> Predicate<E> {
+        self.like(format!("%{}%", escape_like(s)), true)
+    }
+
+    pub fn has_prefix(self, s: &str) -> Predicate<E> {
+        self.like(format!("{}%", escape_like(s)), false)
+    }
+
+    pub fn has_suffix(self, s: &str) -> Predicate<E> {
+        self.like(format!("%{}", escape_like(s)), false)
+    }
+
+    /// A raw `LIKE` pattern, `%` and `_` are wildcards.
+    pub fn like_pattern(self, pattern: &str) -> Predicate<E> {
+        self.like(pattern.to_string(), false)
+    }
+}
+
+impl<E, T, M, K> Column<E, Option<T>, M, K> {
+    pub fn is_null(self) -> Predicate<E> {
+        Predicate::new(Expr::IsNull {
+            column: &self.field.column_name,
+            negated: false,
+        })
+    }
+
+    pub fn is_not_null(self) -> Predicate<E> {
+        Predicate::new(Expr::IsNull {
+            column: &self.field.column_name,
+            negated: true,
+        })
+    }
+}
+
+/// Escapes the wildcards of a `LIKE` pattern with postgres' default escape character.
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Compare {
+        column: &'static str,
+        op: &'static str,
+        value: Value,
+    },
+    In {
+        column: &'static str,
+        values: Vec<Value>,
+        negated: bool,
+    },
+    Like {
+        column: &'static str,
+        pattern: String,
+        case_insensitive: bool,
+    },
+    IsNull {
+        column: &'static str,
+        negated: bool,
+    },
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+}
+impl Expr {
+    /// The SQL of this expression, pushing its parameters onto `params` and numbering them after
+    /// the ones already there.
+    fn render(&self, params: &mut Vec<Value>) -> String {
+        match self {
+            Self::Compare { column, op, value } => {
+                format!(
+                    "{} {} {}",
+                    quote_ident(column),
+                    op,
+                    param(params, value.clone())
+                )
+            }
+            Self::In {
+                values, negated, ..
+            } if values.is_empty() => (if *negated { "TRUE" } else { "FALSE" }).to_string(),
+            Self::In {
+                column,
+                values,
+                negated,
+            } => format!(
+                "{} {}IN ({})",
+                quote_ident(column),
+                if *negated { "NOT " } else { "" },
+                values
+                    .iter()
+                    .map(|v| param(params, v.clone()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Like {
+                column,
+                pattern,
+                case_insensitive,
+            } => format!(
+                "{} {} {}",
+                quote_ident(column),
+                if *case_insensitive { "ILIKE" } else { "LIKE" },
+                param(params, Value::Text(pattern.clone()))
+            ),
+            Self::IsNull { column, negated } => format!(
+                "{} IS {}NULL",
+                quote_ident(column),
+                if *negated { "NOT " } else { "" }
+            ),
+            Self::And(exprs) => Self::render_all(exprs, " AND ", params),
+            Self::Or(exprs) => Self::render_all(exprs, " OR ", params),
+            Self::Not(expr) => format!("NOT ({})", expr.render(params)),
+        }
+    }
+
+    /// Joins `exprs` with `op`, nested `AND`s and `OR`s are parenthesized.
+    fn render_all(exprs: &[Expr], op: &str, params: &mut Vec<Value>) -> String {
+        exprs
+            .iter()
+            .map(|e| match e {
+                Self::And(_) | Self::Or(_) => format!("({})", e.render(params)),
+                _ => e.render(params),
+            })
+            .collect::<Vec<_>>()
+            .join(op)
+    }
+}
+
+fn param(params: &mut Vec<Value>, value: Value) -> String {
+    params.push(value);
+    format!("${}", params.len())
+}
+
+/// A condition on the rows of entity `E`, built from its columns and combined with
+/// [`Predicate::and`], [`Predicate::or`] and `!`.
+pub struct Predicate<E> {
+    expr: Expr,
+    _entity: PhantomData<fn() -> E>,
+}
+impl<E> Clone for Predicate<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.expr.clone())
+    }
+}
+impl<E> std::fmt::Debug for Predicate<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.expr.fmt(f)
+    }
+}
+
+impl<E> Predicate<E> {
+    fn new(expr: Expr) -> Self {
+        Self {
+            expr,
+            _entity: PhantomData,
+        }
+    }
+
+    pub fn and(self, other: Predicate<E>) -> Self {
+        Self::new(match (self.expr, other.expr) {
+            (Expr::And(mut a), Expr::And(b)) => {
+                a.extend(b);
+                Expr::And(a)
+            }
+            (Expr::And(mut a), b) => {
+                a.push(b);
+                Expr::And(a)
+            }
+            (a, b) => Expr::And(vec![a, b]),
+        })
+    }
+
+    pub fn or(self, other: Predicate<E>) -> Self {
+        Self::new(match (self.expr, other.expr) {
+            (Expr::Or(mut a), Expr::Or(b)) => {
+                a.extend(b);
+                Expr::Or(a)
+            }
+            (Expr::Or(mut a), b) => {
+                a.push(b);
+                Expr::Or(a)
+            }
+            (a, b) => Expr::Or(vec![a, b]),
+        })
+    }
+
+    /// The SQL of this predicate, pushing its parameters onto `params` and numbering them after
+    /// the ones already there.
+    pub fn to_sql(&self, params: &mut Vec<Value>) -> String {
+        self.expr.render(params)
+    }
+}
+
+impl<E> std::ops::Not for Predicate<E> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::new(Expr::Not(Box::new(self.expr)))
+    }
+}
+
+/// The order of one column of entity `E`, made with [`Column::asc`] and [`Column::desc`].
+pub struct Order<E> {
+    column: &'static str,
+    descending: bool,
+    _entity: PhantomData<fn() -> E>,
+}
+impl<E> Clone for Order<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.column, self.descending)
+    }
+}
+
+impl<E> Order<E> {
+    fn new(column: &'static str, descending: bool) -> Self {
+        Self {
+            column,
+            descending,
+            _entity: PhantomData,
+        }
+    }
+
+    fn to_sql(&self) -> String {
+        format!(
+            "{} {}",
+            quote_ident(self.column),
+            if self.descending { "DESC" } else { "ASC" }
+        )
+    }
+}
+
+/// A `SELECT` of every column of entity `E`. Filters are `AND`ed together and rows are ordered
+/// by each `order_by` in turn.
+pub struct Query<E> {
+    predicate: Option<Predicate<E>>,
+    order: Vec<Order<E>>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+impl<E> Clone for Query<E> {
+    fn clone(&self) -> Self {
+        Self {
+            predicate: self.predicate.clone(),
+            order: self.order.clone(),
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
+impl<E: Entity> Default for Query<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> Query<E> {
+    pub fn new() -> Self {
+        Self {
+            predicate: None,
+            order: Vec::new(),
+            limit: None,
+            offset: None,
+        }
+    }
+
+    pub fn filter(mut self, predicate: Predicate<E>) -> Self {
+        self.predicate = Some(match self.predicate {
+            Some(p) => p.and(predicate),
+            None => predicate,
+        });
+        self
+    }
+
+    pub fn order_by(mut self, order: Order<E>) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// The query as postgres SQL with `$n` placeholders, along with the parameters to bind to
+    /// them in order.
+    pub fn to_sql(&self) -> (String, Vec<Value>) {
+        let desc = E::descriptor();
+        let mut params = Vec::new();
+
+        let mut sql = format!(
+            "SELECT {} FROM {}",
+            column_list(desc),
+            qualified(&desc.schema_name, &desc.table_name)
+        );
+
+        if let Some(predicate) = &self.predicate {
+            sql.push_str(" WHERE ");
+            sql.push_str(&predicate.to_sql(&mut params));
+        }
+        if !self.order.is_empty() {
+            let order = self.order.iter().map(Order::to_sql).collect::<Vec<_>>();
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&order.join(", "));
+        }
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        if let Some(offset) = self.offset {
+            sql.push_str(&format!(" OFFSET {}", offset));
+        }
+
+        (sql, params)
+    }
+}
+
+/// Every column of `desc`, quoted and separated by commas.
+pub(crate) fn column_list(desc: &EntityDescriptor) -> String {
+    desc.fields
+        .iter()
+        .map(|f| quote_ident(&f.column_name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("50%_off\\"), "50\\%\\_off\\\\");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+}
@@ -0,0 +1,224 @@
+//! Query parameters and the marker traits deciding which predicates a column supports.
+
+use crate::sql::postgres::SqlEnum;
+
+/// A query parameter, typed like the postgres column it is compared with so a driver can bind it
+/// as is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    SmallInt(i16),
+    Int(i32),
+    BigInt(i64),
+    Real(f32),
+    Double(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    /// The label of a postgres enum.
+    Enum(&'static str),
+    Inet(std::net::IpAddr),
+    #[cfg(feature = "uuid")]
+    Uuid(uuid::Uuid),
+    #[cfg(feature = "time")]
+    OffsetDateTime(time::OffsetDateTime),
+    #[cfg(feature = "time")]
+    PrimitiveDateTime(time::PrimitiveDateTime),
+    #[cfg(feature = "time")]
+    Date(time::Date),
+    #[cfg(feature = "time")]
+    Time(time::Time),
+    #[cfg(feature = "chrono")]
+    DateTimeUtc(chrono::DateTime<chrono::Utc>),
+    #[cfg(feature = "chrono")]
+    NaiveDateTime(chrono::NaiveDateTime),
+    #[cfg(feature = "chrono")]
+    NaiveDate(chrono::NaiveDate),
+    #[cfg(feature = "chrono")]
+    NaiveTime(chrono::NaiveTime),
+    #[cfg(feature = "serde_json")]
+    Json(serde_json::Value),
+    #[cfg(feature = "rust_decimal")]
+    Decimal(rust_decimal::Decimal),
+    #[cfg(feature = "ipnetwork")]
+    Cidr(ipnetwork::IpNetwork),
+}
+
+/// A Rust type whose columns can be compared with a parameter.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` columns cannot be compared with a value",
+    note = "implement `graphix::query::ColumnValue` for it to use it in predicates"
+)]
+pub trait ColumnValue {
+    /// The type of the values a column is compared with, the inner type for `Option<T>`.
+    type Param;
+
+    /// The parameter `param` is bound as.
+    fn to_value(param: Self::Param) -> Value;
+}
+
+/// Implemented by columns `LIKE` and its shorthands apply to.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a text column",
+    note = "`contains`, `has_prefix` and the other string predicates only apply to text columns"
+)]
+pub trait TextColumn {}
+
+/// Implemented by columns that can be compared with `<` and `>`, numbers, dates and times.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` columns have no order",
+    note = "`gt`, `lt` and the other range predicates only apply to numbers, dates and times"
+)]
+pub trait OrderedColumn {}
+
+/// Stand-ins for the field's type deciding the predicates of a column with a `sql_type`.
+pub mod kind {
+    use super::{OrderedColumn, TextColumn};
+
+    /// A text column, which has the string predicates.
+    pub struct Text;
+    impl TextColumn for Text {}
+
+    /// A number, date or time column, which has the range predicates.
+    pub struct Ordered;
+    impl OrderedColumn for Ordered {}
+
+    /// Any other column, which only has equality.
+    pub struct Other;
+}
+
+/// A value that can be compared with a column whose parameters are `P`. Anything is comparable
+/// with its own type, strings and bytes can also be borrowed.
+#[diagnostic::on_unimplemented(message = "`{Self}` cannot be compared with a `{P}` column")]
+pub trait IntoParam<P> {
+    fn into_param(self) -> P;
+}
+impl<P> IntoParam<P> for P {
+    fn into_param(self) -> P {
+        self
+    }
+}
+impl IntoParam<String> for &str {
+    fn into_param(self) -> String {
+        self.to_string()
+    }
+}
+impl IntoParam<String> for &String {
+    fn into_param(self) -> String {
+        self.clone()
+    }
+}
+impl IntoParam<Vec<u8>> for &[u8] {
+    fn into_param(self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+/// A value that can be stored in a column whose field is a `T`, anything its parameter can be
+/// made from. Generated setters and lookups take one, so a field type without a
+/// [`ColumnValue`] only fails where it is used.
+#[diagnostic::on_unimplemented(message = "`{Self}` cannot be stored in a `{T}` column")]
+pub trait IntoValue<T> {
+    fn into_value(self) -> Value;
+}
+impl<T: ColumnValue, V: IntoParam<T::Param>> IntoValue<T> for V {
+    fn into_value(self) -> Value {
+        T::to_value(self.into_param())
+    }
+}
+
+macro_rules! impl_column_value {
+    ($($typ:ty [$($marker:ident),*] => |$v:ident| $value:expr),* $(,)?) => {
+        $(
+            impl ColumnValue for $typ {
+                type Param = $typ;
+
+                fn to_value($v: $typ) -> Value {
+                    $value
+                }
+            }
+
+            $(impl $marker for $typ {})*
+        )*
+    };
+}
+
+// unsigned integers are stored bit for bit in the signed column of the same width, matching
+// their `SqlType`, so only `u8` keeps its order
+impl_column_value! {
+    String [TextColumn] => |v| Value::Text(v),
+    char [] => |v| Value::Text(v.to_string()),
+    bool [] => |v| Value::Bool(v),
+    i8 [OrderedColumn] => |v| Value::SmallInt(v.into()),
+    i16 [OrderedColumn] => |v| Value::SmallInt(v),
+    i32 [OrderedColumn] => |v| Value::Int(v),
+    i64 [OrderedColumn] => |v| Value::BigInt(v),
+    isize [OrderedColumn] => |v| Value::BigInt(v as i64),
+    u8 [OrderedColumn] => |v| Value::SmallInt(v.into()),
+    u16 [] => |v| Value::SmallInt(v as i16),
+    u32 [] => |v| Value::Int(v as i32),
+    u64 [] => |v| Value::BigInt(v as i64),
+    usize [] => |v| Value::BigInt(v as i64),
+    f32 [OrderedColumn] => |v| Value::Real(v),
+    f64 [OrderedColumn] => |v| Value::Double(v),
+    Vec<u8> [] => |v| Value::Bytes(v),
+    std::net::IpAddr [] => |v| Value::Inet(v),
+    std::net::Ipv4Addr [] => |v| Value::Inet(v.into()),
+    std::net::Ipv6Addr [] => |v| Value::Inet(v.into()),
+}
+
+#[cfg(feature = "uuid")]
+impl_column_value! {
+    uuid::Uuid [] => |v| Value::Uuid(v),
+}
+
+#[cfg(feature = "time")]
+impl_column_value! {
+    time::OffsetDateTime [OrderedColumn] => |v| Value::OffsetDateTime(v),
+    time::PrimitiveDateTime [OrderedColumn] => |v| Value::PrimitiveDateTime(v),
+    time::Date [OrderedColumn] => |v| Value::Date(v),
+    time::Time [OrderedColumn] => |v| Value::Time(v),
+}
+
+#[cfg(feature = "chrono")]
+impl_column_value! {
+    chrono::DateTime<chrono::Utc> [OrderedColumn] => |v| Value::DateTimeUtc(v),
+    chrono::NaiveDateTime [OrderedColumn] => |v| Value::NaiveDateTime(v),
+    chrono::NaiveDate [OrderedColumn] => |v| Value::NaiveDate(v),
+    chrono::NaiveTime [OrderedColumn] => |v| Value::NaiveTime(v),
+}
+
+#[cfg(feature = "serde_json")]
+impl_column_value! {
+    serde_json::Value [] => |v| Value::Json(v),
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_column_value! {
+    rust_decimal::Decimal [OrderedColumn] => |v| Value::Decimal(v),
+}
+
+#[cfg(feature = "ipnetwork")]
+impl_column_value! {
+    ipnetwork::IpNetwork [] => |v| Value::Cidr(v),
+    ipnetwork::Ipv4Network [] => |v| Value::Cidr(v.into()),
+    ipnetwork::Ipv6Network [] => |v| Value::Cidr(v.into()),
+}
+
+impl<T: SqlEnum> ColumnValue for T {
+    type Param = T;
+
+    fn to_value(param: T) -> Value {
+        Value::Enum(param.as_label())
+    }
+}
+
+impl<T: ColumnValue> ColumnValue for Option<T> {
+    type Param = T::Param;
+
+    fn to_value(param: T::Param) -> Value {
+        T::to_value(param)
+    }
+}
+impl<T: TextColumn> TextColumn for Option<T> {}
+impl<T: OrderedColumn> OrderedColumn for Option<T> {}
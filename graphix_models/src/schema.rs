@@ -0,0 +1,196 @@
+use crate::descriptor::{EntityDescriptor, EnumDescriptor};
+use crate::entity::{enum_atlas_hcl, Entity};
+use hcl::{Block, Body};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Registers an entity with [`Schema::registered`], submitted by `#[derive(Entity)]`.
+pub struct EntityRegistration {
+    descriptor: fn() -> &'static EntityDescriptor,
+}
+impl EntityRegistration {
+    pub const fn new<E: Entity>() -> Self {
+        Self {
+            descriptor: E::descriptor,
+        }
+    }
+}
+inventory::collect!(EntityRegistration);
+
+/// A whole database schema: the tables of a set of entities along with their join tables, the
+/// enums they use and the schemas they live in. Everything is ordered by schema and name so the
+/// output doesn't depend on the order entities were added or linked in.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    /// Keyed by (schema name, table name).
+    tables: BTreeMap<(String, String), EntityDescriptor>,
+    /// Keyed by (schema name, enum name).
+    enums: BTreeMap<(String, String), EnumDescriptor>,
+}
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every entity deriving `Entity` in the program.
+    pub fn registered() -> Self {
+        let mut schema = Self::new();
+        for registration in inventory::iter::<EntityRegistration> {
+            schema.add((registration.descriptor)());
+        }
+
+        schema
+    }
+
+    /// Adds an entity's table, its join tables and enums. A table with the same schema and name
+    /// as one already added replaces it. Join tables are kept as tables of their own, so the
+    /// stored entity has no `join_tables`.
+    pub fn add(&mut self, desc: &EntityDescriptor) -> &mut Self {
+        for table in std::iter::once(desc).chain(&desc.join_tables) {
+            for e in &table.enums {
+                self.enums
+                    .insert((e.schema_name.clone(), e.name.clone()), e.clone());
+            }
+
+            // join tables are stored as tables of their own
+            self.tables.insert(
+                (table.schema_name.clone(), table.table_name.clone()),
+                EntityDescriptor {
+                    join_tables: vec![],
+                    ..table.clone()
+                },
+            );
+        }
+
+        self
+    }
+
+    /// Adds an enum on its own, e.g. one no table uses yet.
+    pub fn add_enum(&mut self, desc: EnumDescriptor) -> &mut Self {
+        self.enums
+            .insert((desc.schema_name.clone(), desc.name.clone()), desc);
+        self
+    }
+
+    /// All tables, join tables included.
+    pub fn tables(&self) -> impl Iterator<Item = &EntityDescriptor> {
+        self.tables.values()
+    }
+
+    /// All tables, ordered so every table comes after the tables its foreign keys reference.
+    /// Otherwise tables stay in name order, which is also the fallback for reference cycles.
+    pub fn tables_by_dependency(&self) -> Vec<&EntityDescriptor> {
+        let depends_on = |t: &EntityDescriptor, other: &EntityDescriptor| {
+            (t.schema_name != other.schema_name || t.table_name != other.table_name)
+                && t.foreign_keys().any(|e| {
+                    e.target_schema == other.schema_name && e.target_table == other.table_name
+                })
+        };
+
+        let mut remaining: Vec<&EntityDescriptor> = self.tables().collect();
+        let mut ordered = Vec::with_capacity(remaining.len());
+
+        while !remaining.is_empty() {
+            let next = remaining
+                .iter()
+                .position(|t| !remaining.iter().any(|other| depends_on(t, other)))
+                .unwrap_or(0);
+            ordered.push(remaining.remove(next));
+        }
+
+        ordered
+    }
+
+    pub fn table(&self, schema_name: &str, table_name: &str) -> Option<&EntityDescriptor> {
+        self.tables
+            .get(&(schema_name.to_string(), table_name.to_string()))
+    }
+
+    pub fn enums(&self) -> impl Iterator<Item = &EnumDescriptor> {
+        self.enums.values()
+    }
+
+    /// The names of the schemas referenced by tables and enums, e.g. `public` for `schema.public`.
+    pub fn schemas(&self) -> BTreeSet<&str> {
+        self.tables
+            .keys()
+            .chain(self.enums.keys())
+            .map(|(schema, _)| schema_label(schema))
+            .collect()
+    }
+
+    /// The complete Atlas HCL document, `schema` blocks first followed by enums and tables.
+    pub fn as_atlas_hcl(&self) -> Body {
+        Body::builder()
+            .add_blocks(
+                self.schemas()
+                    .into_iter()
+                    .map(|name| Block::builder("schema").add_label(name).build()),
+            )
+            .add_blocks(self.enums().map(enum_atlas_hcl))
+            .add_blocks(self.tables().map(|t| t.as_atlas_hcl()))
+            .build()
+    }
+}
+
+/// Version of the snapshot format, bumped on incompatible changes.
+#[cfg(feature = "serde")]
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Snapshot {
+    version: u32,
+    enums: Vec<EnumDescriptor>,
+    tables: Vec<EntityDescriptor>,
+}
+
+#[cfg(feature = "serde")]
+impl Schema {
+    /// The schema as pretty printed JSON. Enums and tables are in the same order as everywhere
+    /// else, so the same schema always gives the same snapshot and changes show up as small diffs.
+    pub fn to_snapshot(&self) -> String {
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            enums: self.enums().cloned().collect(),
+            tables: self.tables().cloned().collect(),
+        };
+
+        let mut json =
+            serde_json::to_string_pretty(&snapshot).expect("descriptors serialize to JSON");
+        json.push('\n');
+        json
+    }
+
+    pub fn from_snapshot(json: &str) -> Result<Self, serde_json::Error> {
+        let snapshot: Snapshot = serde_json::from_str(json)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported snapshot version {}, expected {}",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+
+        let mut schema: Schema = snapshot.tables.iter().collect();
+        for e in snapshot.enums {
+            schema.add_enum(e);
+        }
+
+        Ok(schema)
+    }
+}
+
+impl<'a> FromIterator<&'a EntityDescriptor> for Schema {
+    fn from_iter<I: IntoIterator<Item = &'a EntityDescriptor>>(iter: I) -> Self {
+        let mut schema = Self::new();
+        for desc in iter {
+            schema.add(desc);
+        }
+
+        schema
+    }
+}
+
+/// The name of a schema from its reference, `public` for `schema.public`.
+pub fn schema_label(reference: &str) -> &str {
+    reference.strip_prefix("schema.").unwrap_or(reference)
+}
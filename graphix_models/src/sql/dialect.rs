@@ -0,0 +1,35 @@
+use crate::descriptor::{EntityDescriptor, EntityFieldDescriptor};
+use crate::schema::Schema;
+use hcl::{Block, Body};
+
+/// A database graphix renders schemas for. Every dialect lowers the [`LogicalType`] of a column
+/// to a column type of its own, so the same entities produce HCL and DDL for any of them, e.g.
+/// `MySql::default().schema_hcl(&schema)` next to `Postgres.schema_hcl(&schema)`.
+///
+/// [`LogicalType`]: crate::sql::LogicalType
+pub trait Dialect {
+    /// The dialect's own column types.
+    type ColumnType: std::fmt::Display;
+    /// Why a schema can't be expressed in this dialect, `Infallible` for dialects that can
+    /// express every logical type.
+    type Error: std::error::Error;
+
+    /// The type of one of `table`'s columns.
+    fn column_type(
+        &self,
+        table: &EntityDescriptor,
+        field: &EntityFieldDescriptor,
+    ) -> Result<Self::ColumnType, Self::Error>;
+
+    /// Quotes a table, column or index name.
+    fn quote_ident(&self, ident: &str) -> String;
+
+    /// The Atlas HCL `table` block of a single table.
+    fn table_hcl(&self, table: &EntityDescriptor) -> Result<Block, Self::Error>;
+
+    /// The Atlas HCL of a whole schema, including the `schema` blocks the tables refer to.
+    fn schema_hcl(&self, schema: &Schema) -> Result<Body, Self::Error>;
+
+    /// The statements creating `schema` from scratch, in an order the database accepts.
+    fn schema_statements(&self, schema: &Schema) -> Result<Vec<String>, Self::Error>;
+}
@@ -0,0 +1,209 @@
+//! Dialect neutral column types. Descriptors hold a [`LogicalType`] and each
+//! [`Dialect`](crate::sql::Dialect) lowers it to a type of its own.
+
+use crate::sql::postgres;
+use std::fmt::Formatter;
+
+/// The type of a column independent of the database. Types only postgres has, such as ranges,
+/// `tsvector` or `inet`, are kept as [`LogicalType::Postgres`] and left to each dialect to map or
+/// reject.
+///
+/// Converting from and to [`postgres::ColumnType`] is lossless, and logical types are written
+/// with the same SQL spelling, so snapshots read the same as before dialects existed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogicalType {
+    Bool,
+    SmallInt,
+    Int,
+    BigInt,
+    /// Integers filled in by the database, `serial` in postgres and `AUTO_INCREMENT` in MySQL.
+    SmallSerial,
+    Serial,
+    BigSerial,
+    Real,
+    Double,
+    Decimal {
+        precision: Option<usize>,
+        scale: Option<usize>,
+    },
+    Text,
+    VarChar(Option<usize>),
+    Char(Option<usize>),
+    Bytes,
+    Uuid,
+    Date,
+    Time,
+    /// Date and time without a time zone, with the given fractional second digits.
+    Timestamp(Option<usize>),
+    TimestampTz,
+    Json,
+    /// An enum by name, its values are in the table's `enums`.
+    Enum(String),
+    Array(Box<LogicalType>),
+    Postgres(postgres::ColumnType),
+}
+impl LogicalType {
+    /// The type of a column referencing a column of this type, serials become the integer they
+    /// are backed by.
+    pub fn as_reference(&self) -> Self {
+        match self {
+            Self::SmallSerial => Self::SmallInt,
+            Self::Serial => Self::Int,
+            Self::BigSerial => Self::BigInt,
+            other => other.clone(),
+        }
+    }
+
+    /// Whether the database fills in columns of this type.
+    pub fn is_serial(&self) -> bool {
+        matches!(self, Self::SmallSerial | Self::Serial | Self::BigSerial)
+    }
+
+    /// The enum a column of this type depends on, looking through arrays.
+    pub fn enum_name(&self) -> Option<&str> {
+        match self {
+            Self::Enum(name) => Some(name),
+            Self::Array(of) => of.enum_name(),
+            _ => None,
+        }
+    }
+}
+impl From<postgres::ColumnType> for LogicalType {
+    fn from(typ: postgres::ColumnType) -> Self {
+        use postgres::ColumnType as Pg;
+
+        match typ {
+            Pg::Boolean => Self::Bool,
+            Pg::SmallInt => Self::SmallInt,
+            Pg::Integer => Self::Int,
+            Pg::BigInt => Self::BigInt,
+            Pg::SmallSerial => Self::SmallSerial,
+            Pg::Serial => Self::Serial,
+            Pg::BigSerial => Self::BigSerial,
+            Pg::Real => Self::Real,
+            Pg::DoublePrecision => Self::Double,
+            Pg::Numeric(None) => Self::Decimal {
+                precision: None,
+                scale: None,
+            },
+            Pg::Numeric(Some(args)) => match args[..] {
+                [precision] => Self::Decimal {
+                    precision: Some(precision),
+                    scale: None,
+                },
+                [precision, scale] => Self::Decimal {
+                    precision: Some(precision),
+                    scale: Some(scale),
+                },
+                _ => Self::Postgres(Pg::Numeric(Some(args))),
+            },
+            Pg::Text => Self::Text,
+            Pg::VarChar(length) => Self::VarChar(length),
+            Pg::Char(length) => Self::Char(length),
+            Pg::ByteA => Self::Bytes,
+            Pg::Uuid => Self::Uuid,
+            Pg::Date => Self::Date,
+            Pg::Time => Self::Time,
+            Pg::Timestamp(precision) => Self::Timestamp(precision),
+            Pg::TimestampTz => Self::TimestampTz,
+            Pg::Jsonb => Self::Json,
+            Pg::Enum(name) => Self::Enum(name),
+            Pg::Array(of) => Self::Array(Box::new(Self::from(*of))),
+            // aliases and spellings such as `int` or `json` stay as written
+            other => Self::Postgres(other),
+        }
+    }
+}
+impl From<&LogicalType> for postgres::ColumnType {
+    fn from(typ: &LogicalType) -> Self {
+        use LogicalType as L;
+
+        match typ {
+            L::Bool => Self::Boolean,
+            L::SmallInt => Self::SmallInt,
+            L::Int => Self::Integer,
+            L::BigInt => Self::BigInt,
+            L::SmallSerial => Self::SmallSerial,
+            L::Serial => Self::Serial,
+            L::BigSerial => Self::BigSerial,
+            L::Real => Self::Real,
+            L::Double => Self::DoublePrecision,
+            L::Decimal { precision, scale } => {
+                Self::Numeric(precision.map(|p| std::iter::once(p).chain(*scale).collect()))
+            }
+            L::Text => Self::Text,
+            L::VarChar(length) => Self::VarChar(*length),
+            L::Char(length) => Self::Char(*length),
+            L::Bytes => Self::ByteA,
+            L::Uuid => Self::Uuid,
+            L::Date => Self::Date,
+            L::Time => Self::Time,
+            L::Timestamp(precision) => Self::Timestamp(*precision),
+            L::TimestampTz => Self::TimestampTz,
+            L::Json => Self::Jsonb,
+            L::Enum(name) => Self::Enum(name.clone()),
+            L::Array(of) => Self::Array(Box::new(Self::from(&**of))),
+            L::Postgres(typ) => typ.clone(),
+        }
+    }
+}
+impl std::fmt::Display for LogicalType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", postgres::ColumnType::from(self))
+    }
+}
+impl std::str::FromStr for LogicalType {
+    type Err = postgres::ParseColumnTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<postgres::ColumnType>().map(Self::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LogicalType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LogicalType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use postgres::ColumnType as Pg;
+
+    #[test]
+    fn test_postgres_round_trip() {
+        let cases = [
+            (Pg::Integer, LogicalType::Int),
+            (Pg::Int, LogicalType::Postgres(Pg::Int)),
+            (
+                Pg::Numeric(Some(vec![12, 2])),
+                LogicalType::Decimal {
+                    precision: Some(12),
+                    scale: Some(2),
+                },
+            ),
+            (Pg::Jsonb, LogicalType::Json),
+            (Pg::Json, LogicalType::Postgres(Pg::Json)),
+            (
+                Pg::Array(Box::new(Pg::Enum("status".to_string()))),
+                LogicalType::Array(Box::new(LogicalType::Enum("status".to_string()))),
+            ),
+            (Pg::TsVector, LogicalType::Postgres(Pg::TsVector)),
+        ];
+
+        for (pg, logical) in cases {
+            assert_eq!(LogicalType::from(pg.clone()), logical);
+            assert_eq!(Pg::from(&logical), pg);
+            assert_eq!(logical.to_string(), pg.to_string());
+        }
+    }
+}
@@ -1 +1,8 @@
-pub mod postgres;
\ No newline at end of file
+pub mod dialect;
+pub mod logical;
+pub mod mysql;
+pub mod postgres;
+pub mod sqlite;
+
+pub use dialect::Dialect;
+pub use logical::LogicalType;
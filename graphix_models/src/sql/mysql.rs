@@ -0,0 +1,466 @@
+//! MySQL support. Logical types are lowered to MySQL's and rendered as Atlas HCL or DDL for a
+//! MySQL database. Arrays, ranges, full text search types and a few other
+//! postgres features have no MySQL equivalent and are reported as an [`UnsupportedError`].
+
+use crate::descriptor::{
+    EntityDescriptor, EntityFieldDescriptor, EnumDescriptor, IndexDescriptor, IndexMethod, Unsigned,
+};
+use crate::schema::Schema;
+use crate::sql::{postgres, Dialect, LogicalType};
+use hcl::expr::{Expression, FuncCall};
+use hcl::{Block, Body, Variable};
+use std::fmt::Formatter;
+
+pub mod atlas;
+pub mod ddl;
+
+/// The MySQL column types graphix maps to. Unlike postgres, enums are declared inline with their
+/// values and integers can be unsigned.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    Bool,
+    TinyInt {
+        unsigned: bool,
+    },
+    SmallInt {
+        unsigned: bool,
+    },
+    MediumInt {
+        unsigned: bool,
+    },
+    Int {
+        unsigned: bool,
+    },
+    BigInt {
+        unsigned: bool,
+    },
+    /// Precision and scale, `decimal` on its own is `decimal(10,0)`.
+    Decimal(Option<(usize, usize)>),
+    Float,
+    Double,
+    Bit(usize),
+    Char(usize),
+    VarChar(usize),
+    Text,
+    MediumText,
+    LongText,
+    Binary(usize),
+    VarBinary(usize),
+    Blob,
+    LongBlob,
+    Date,
+    Time,
+    DateTime(Option<usize>),
+    Timestamp(Option<usize>),
+    Year,
+    Json,
+    Enum(Vec<String>),
+    Point,
+    Polygon,
+}
+impl ColumnType {
+    /// The MySQL type of a logical type. Enum values are looked up in `enums`. Unlimited types
+    /// get the widest MySQL equivalent: `text` is `longtext`, `bytea` is `longblob` and `numeric`
+    /// without a precision is `decimal(65,30)`.
+    pub fn from_logical(
+        typ: &LogicalType,
+        enums: &[EnumDescriptor],
+    ) -> Result<Self, PostgresFeature> {
+        use postgres::ColumnType as Pg;
+
+        Ok(match typ {
+            LogicalType::Bool => Self::Bool,
+            LogicalType::SmallInt | LogicalType::SmallSerial => Self::SmallInt { unsigned: false },
+            LogicalType::Int | LogicalType::Serial => Self::Int { unsigned: false },
+            LogicalType::BigInt | LogicalType::BigSerial => Self::BigInt { unsigned: false },
+            LogicalType::Real => Self::Float,
+            LogicalType::Double => Self::Double,
+            LogicalType::Decimal {
+                precision: Some(precision),
+                scale,
+            } => Self::Decimal(Some((*precision, scale.unwrap_or(0)))),
+            LogicalType::Decimal { .. } => Self::Decimal(Some((65, 30))),
+            LogicalType::Text | LogicalType::VarChar(None) => Self::LongText,
+            LogicalType::VarChar(Some(length)) => Self::VarChar(*length),
+            LogicalType::Char(length) => Self::Char(length.unwrap_or(1)),
+            LogicalType::Bytes => Self::LongBlob,
+            LogicalType::Uuid => Self::Char(36),
+            LogicalType::Date => Self::Date,
+            LogicalType::Time => Self::Time,
+            // postgres keeps microseconds, MySQL drops them unless asked
+            LogicalType::Timestamp(precision) => Self::DateTime(Some(precision.unwrap_or(6))),
+            LogicalType::TimestampTz => Self::Timestamp(Some(6)),
+            LogicalType::Json => Self::Json,
+            LogicalType::Enum(name) => match enums.iter().find(|e| e.name == *name) {
+                Some(e) => Self::Enum(e.values.clone()),
+                None => return Err(PostgresFeature::NamedEnum(name.clone())),
+            },
+            LogicalType::Array(_) => return Err(PostgresFeature::Array(typ.into())),
+            LogicalType::Postgres(pg) => match pg {
+                Pg::Int => Self::Int { unsigned: false },
+                Pg::Float(precision) if *precision <= 24 => Self::Float,
+                Pg::Float(_) => Self::Double,
+                Pg::Numeric(_) => Self::Decimal(Some((65, 30))),
+                Pg::Money => Self::Decimal(Some((19, 2))),
+                Pg::Bit(size) => Self::Bit(size.unwrap_or(1)),
+                Pg::Xml => Self::LongText,
+                Pg::Json => Self::Json,
+                Pg::INet => Self::VarChar(45),
+                Pg::Cidr => Self::VarChar(49),
+                Pg::MacAddr => Self::VarChar(17),
+                Pg::MacAddr8 => Self::VarChar(23),
+                Pg::Point => Self::Point,
+                Pg::Polygon => Self::Polygon,
+                Pg::Int4Range
+                | Pg::Int8Range
+                | Pg::NumRange
+                | Pg::TsRange
+                | Pg::TsTzRange
+                | Pg::DateRange
+                | Pg::Int4MultiRange
+                | Pg::Int8MultiRange
+                | Pg::NumMultiRange
+                | Pg::TsMultiRange
+                | Pg::TsTzMultiRange
+                | Pg::DateMultiRange => return Err(PostgresFeature::Range(pg.clone())),
+                Pg::TsVector | Pg::TsQuery => return Err(PostgresFeature::TextSearch(pg.clone())),
+                // everything with a neutral equivalent was converted to it
+                _ => return Err(PostgresFeature::Type(pg.clone())),
+            },
+        })
+    }
+
+    /// The MySQL type of one of `table`'s columns. Text columns that are part of a key become
+    /// `varchar(255)`, MySQL can't index a `longtext` without a prefix length. Unsigned Rust
+    /// integers get unsigned columns of their own width.
+    pub fn for_field(
+        table: &EntityDescriptor,
+        field: &EntityFieldDescriptor,
+    ) -> Result<Self, UnsupportedError> {
+        let typ = Self::from_logical(&field.sql_type, &table.enums).map_err(|feature| {
+            UnsupportedError {
+                table: table.table_name.clone(),
+                item: format!("column `{}`", field.column_name),
+                feature,
+            }
+        })?;
+
+        Ok(match (typ, field.unsigned) {
+            (Self::LongText, _) if is_key_column(table, &field.column_name) => Self::VarChar(255),
+            (Self::SmallInt { .. } | Self::Int { .. } | Self::BigInt { .. }, Some(width)) => {
+                match width {
+                    Unsigned::U8 => Self::TinyInt { unsigned: true },
+                    Unsigned::U16 => Self::SmallInt { unsigned: true },
+                    Unsigned::U32 => Self::Int { unsigned: true },
+                    Unsigned::U64 => Self::BigInt { unsigned: true },
+                }
+            }
+            (typ, _) => typ,
+        })
+    }
+
+    pub fn is_unsigned(&self) -> bool {
+        matches!(
+            self,
+            Self::TinyInt { unsigned: true }
+                | Self::SmallInt { unsigned: true }
+                | Self::MediumInt { unsigned: true }
+                | Self::Int { unsigned: true }
+                | Self::BigInt { unsigned: true }
+        )
+    }
+
+    /// The expression Atlas HCL uses for this type, e.g. `varchar(255)` or `enum("a","b")`.
+    /// Signedness isn't part of the type in Atlas, it's the column's `unsigned` attribute.
+    pub fn as_atlas_expr(&self) -> Expression {
+        if let Self::Enum(values) = self {
+            return values
+                .iter()
+                .fold(FuncCall::builder("enum"), |call, value| {
+                    call.arg(value.clone())
+                })
+                .build()
+                .into();
+        }
+
+        let name = self.to_string();
+        let name = name.trim_end_matches(" unsigned");
+        match name.split_once('(') {
+            Some((func, args)) => args
+                .trim_end_matches(')')
+                .split(',')
+                .fold(FuncCall::builder(func), |call, arg| {
+                    call.arg(arg.parse::<u64>().expect("type arguments are integers"))
+                })
+                .build()
+                .into(),
+            None => Variable::new(name)
+                .expect("type names are valid identifiers")
+                .into(),
+        }
+    }
+}
+impl std::fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let unsigned = if self.is_unsigned() { " unsigned" } else { "" };
+
+        match self {
+            Self::Bool => write!(f, "bool"),
+            Self::TinyInt { .. } => write!(f, "tinyint{}", unsigned),
+            Self::SmallInt { .. } => write!(f, "smallint{}", unsigned),
+            Self::MediumInt { .. } => write!(f, "mediumint{}", unsigned),
+            Self::Int { .. } => write!(f, "int{}", unsigned),
+            Self::BigInt { .. } => write!(f, "bigint{}", unsigned),
+            Self::Decimal(Some((precision, scale))) => {
+                write!(f, "decimal({},{})", precision, scale)
+            }
+            Self::Decimal(None) => write!(f, "decimal"),
+            Self::Float => write!(f, "float"),
+            Self::Double => write!(f, "double"),
+            Self::Bit(size) => write!(f, "bit({})", size),
+            Self::Char(length) => write!(f, "char({})", length),
+            Self::VarChar(length) => write!(f, "varchar({})", length),
+            Self::Text => write!(f, "text"),
+            Self::MediumText => write!(f, "mediumtext"),
+            Self::LongText => write!(f, "longtext"),
+            Self::Binary(length) => write!(f, "binary({})", length),
+            Self::VarBinary(length) => write!(f, "varbinary({})", length),
+            Self::Blob => write!(f, "blob"),
+            Self::LongBlob => write!(f, "longblob"),
+            Self::Date => write!(f, "date"),
+            Self::Time => write!(f, "time"),
+            Self::DateTime(Some(precision)) => write!(f, "datetime({})", precision),
+            Self::DateTime(None) => write!(f, "datetime"),
+            Self::Timestamp(Some(precision)) => write!(f, "timestamp({})", precision),
+            Self::Timestamp(None) => write!(f, "timestamp"),
+            Self::Year => write!(f, "year"),
+            Self::Json => write!(f, "json"),
+            Self::Enum(values) => {
+                let values = values
+                    .iter()
+                    .map(|v| postgres::ddl::quote_literal(v))
+                    .collect::<Vec<_>>();
+                write!(f, "enum({})", values.join(","))
+            }
+            Self::Point => write!(f, "point"),
+            Self::Polygon => write!(f, "polygon"),
+        }
+    }
+}
+
+/// Whether `column` is part of the primary key, an index or a foreign key of `table`.
+fn is_key_column(table: &EntityDescriptor, column: &str) -> bool {
+    use crate::descriptor::IndexPart;
+
+    table.primary_key.iter().any(|c| c == column)
+        || table
+            .foreign_keys()
+            .any(|e| e.columns.iter().any(|c| c == column))
+        || table.indexes.iter().any(|i| {
+            i.parts
+                .iter()
+                .any(|p| matches!(p, IndexPart::Column(c) if c == column))
+        })
+}
+
+/// Fails on indexes MySQL has no equivalent for: `gin`, `gist` and `brin` indexes and partial
+/// indexes.
+pub(crate) fn check_index(
+    table: &EntityDescriptor,
+    index: &IndexDescriptor,
+) -> Result<(), UnsupportedError> {
+    let feature = match index.method {
+        Some(method @ (IndexMethod::Gin | IndexMethod::Gist | IndexMethod::Brin)) => {
+            PostgresFeature::IndexMethod(method)
+        }
+        _ if index.predicate.is_some() => PostgresFeature::PartialIndex,
+        _ => return Ok(()),
+    };
+
+    Err(UnsupportedError {
+        table: table.table_name.clone(),
+        item: format!("index `{}`", index.name),
+        feature,
+    })
+}
+
+/// A postgres feature with no MySQL equivalent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostgresFeature {
+    Array(postgres::ColumnType),
+    Range(postgres::ColumnType),
+    TextSearch(postgres::ColumnType),
+    /// Any other postgres only type, e.g. `interval` or a domain.
+    Type(postgres::ColumnType),
+    /// A postgres enum the table doesn't carry the values of. MySQL enums are declared inline, so
+    /// there's nothing to refer to by name.
+    NamedEnum(String),
+    IndexMethod(IndexMethod),
+    PartialIndex,
+}
+impl std::fmt::Display for PostgresFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Array(typ) => write!(f, "an array type (`{}`)", typ),
+            Self::Range(typ) => write!(f, "a range type (`{}`)", typ),
+            Self::TextSearch(typ) => write!(f, "a full text search type (`{}`)", typ),
+            Self::Type(typ) => write!(f, "the type `{}`", typ),
+            Self::NamedEnum(name) => write!(f, "the named enum `{}`", name),
+            Self::IndexMethod(method) => write!(f, "the `{}` index method", method),
+            Self::PartialIndex => write!(f, "a partial index"),
+        }
+    }
+}
+
+/// A table that can't be expressed on MySQL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedError {
+    pub table: String,
+    /// What uses the feature, e.g. "column `tags`".
+    pub item: String,
+    pub feature: PostgresFeature,
+}
+impl std::fmt::Display for UnsupportedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}`: {} uses {}, which MySQL doesn't support",
+            self.table, self.item, self.feature
+        )
+    }
+}
+impl std::error::Error for UnsupportedError {}
+
+/// The character set and collation of a MySQL schema and its tables.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Charset {
+    pub charset: String,
+    pub collate: String,
+}
+impl Default for Charset {
+    /// `utf8mb4` with MySQL 8's default collation.
+    fn default() -> Self {
+        Self {
+            charset: "utf8mb4".to_string(),
+            collate: "utf8mb4_0900_ai_ci".to_string(),
+        }
+    }
+}
+
+/// MySQL, rendered with the same charset and collation for every table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MySql {
+    pub charset: Charset,
+}
+impl Dialect for MySql {
+    type ColumnType = ColumnType;
+    type Error = UnsupportedError;
+
+    fn column_type(
+        &self,
+        table: &EntityDescriptor,
+        field: &EntityFieldDescriptor,
+    ) -> Result<ColumnType, UnsupportedError> {
+        ColumnType::for_field(table, field)
+    }
+
+    fn quote_ident(&self, ident: &str) -> String {
+        ddl::quote_ident(ident)
+    }
+
+    fn table_hcl(&self, table: &EntityDescriptor) -> Result<Block, UnsupportedError> {
+        atlas::table_hcl(table, &self.charset)
+    }
+
+    fn schema_hcl(&self, schema: &Schema) -> Result<Body, UnsupportedError> {
+        atlas::schema_hcl(schema, &self.charset)
+    }
+
+    fn schema_statements(&self, schema: &Schema) -> Result<Vec<String>, UnsupportedError> {
+        ddl::schema_statements(schema, &self.charset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use postgres::ColumnType as Pg;
+
+    #[test]
+    fn test_from_logical() {
+        let enums = [EnumDescriptor {
+            name: "status".to_string(),
+            schema_name: "schema.public".to_string(),
+            values: vec!["active".to_string(), "banned".to_string()],
+        }];
+        let cases = [
+            (Pg::Boolean, ColumnType::Bool),
+            (Pg::BigSerial, ColumnType::BigInt { unsigned: false }),
+            (Pg::Float(53), ColumnType::Double),
+            (
+                Pg::Numeric(Some(vec![10, 2])),
+                ColumnType::Decimal(Some((10, 2))),
+            ),
+            (Pg::Numeric(None), ColumnType::Decimal(Some((65, 30)))),
+            (Pg::Text, ColumnType::LongText),
+            (Pg::VarChar(Some(64)), ColumnType::VarChar(64)),
+            (Pg::Uuid, ColumnType::Char(36)),
+            (Pg::TimestampTz, ColumnType::Timestamp(Some(6))),
+            (Pg::Jsonb, ColumnType::Json),
+            (
+                Pg::Enum("status".to_string()),
+                ColumnType::Enum(vec!["active".to_string(), "banned".to_string()]),
+            ),
+        ];
+
+        for (pg, mysql) in cases {
+            let logical = LogicalType::from(pg.clone());
+            assert_eq!(
+                ColumnType::from_logical(&logical, &enums),
+                Ok(mysql),
+                "{}",
+                pg
+            );
+        }
+
+        let tags = Pg::Array(Box::new(Pg::Text));
+        assert_eq!(
+            ColumnType::from_logical(&tags.clone().into(), &enums),
+            Err(PostgresFeature::Array(tags))
+        );
+        assert_eq!(
+            ColumnType::from_logical(&Pg::TsTzRange.into(), &enums),
+            Err(PostgresFeature::Range(Pg::TsTzRange))
+        );
+        assert_eq!(
+            ColumnType::from_logical(&Pg::TsVector.into(), &enums),
+            Err(PostgresFeature::TextSearch(Pg::TsVector))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            ColumnType::Int { unsigned: true }.to_string(),
+            "int unsigned"
+        );
+        assert_eq!(
+            ColumnType::Decimal(Some((19, 2))).to_string(),
+            "decimal(19,2)"
+        );
+        assert_eq!(
+            ColumnType::Enum(vec!["a".to_string(), "o'k".to_string()]).to_string(),
+            "enum('a','o''k')"
+        );
+        assert_eq!(
+            ColumnType::Int { unsigned: true }
+                .as_atlas_expr()
+                .to_string(),
+            "int"
+        );
+        assert_eq!(
+            ColumnType::VarChar(255).as_atlas_expr().to_string(),
+            "varchar(255)"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Atlas HCL for a MySQL database. Blocks have the same shape as the postgres ones, but columns
+//! carry MySQL types, enums are inline and schemas and tables declare their charset and collation.
+
+use crate::descriptor::{ColumnDefault, EntityDescriptor, EntityFieldDescriptor};
+use crate::entity::{default_atlas_expr, foreign_key_atlas_hcl, index_atlas_hcl, traversal};
+use crate::schema::Schema;
+use crate::sql::mysql::{check_index, ddl, Charset, ColumnType, UnsupportedError};
+use crate::sql::LogicalType;
+use hcl::expr::FuncCall;
+use hcl::{Block, Body, Expression};
+
+/// A `column` block. Serial columns are `auto_increment`.
+pub fn column_hcl(
+    table: &EntityDescriptor,
+    field: &EntityFieldDescriptor,
+) -> Result<Block, UnsupportedError> {
+    let typ = ColumnType::for_field(table, field)?;
+
+    let mut column = Block::builder("column")
+        .add_label(field.column_name.clone())
+        .add_attribute(("type", typ.as_atlas_expr()));
+
+    if typ.is_unsigned() {
+        column = column.add_attribute(("unsigned", true));
+    }
+    column = column.add_attribute(("null", field.nullable));
+    if let Some(default) = &field.default {
+        column = column.add_attribute(("default", default_hcl(&typ, default)));
+    }
+    if matches!(
+        field.sql_type,
+        LogicalType::SmallSerial | LogicalType::Serial | LogicalType::BigSerial
+    ) {
+        column = column.add_attribute(("auto_increment", true));
+    }
+
+    Ok(column.build())
+}
+
+/// A `table` block. Fails on columns or indexes MySQL can't express.
+pub fn table_hcl(desc: &EntityDescriptor, charset: &Charset) -> Result<Block, UnsupportedError> {
+    let mut builder = Block::builder("table")
+        .add_label(&desc.table_name)
+        .add_attribute(("schema", traversal(&desc.schema_name)))
+        .add_attribute(("charset", charset.charset.clone()))
+        .add_attribute(("collate", charset.collate.clone()));
+
+    for field in &desc.fields {
+        builder = builder.add_block(column_hcl(desc, field)?);
+    }
+
+    if !desc.primary_key.is_empty() {
+        builder = builder.add_block(
+            Block::builder("primary_key")
+                .add_attribute((
+                    "columns",
+                    desc.primary_key
+                        .iter()
+                        .map(|col| traversal(&format!("column.{}", col)))
+                        .collect::<Vec<_>>(),
+                ))
+                .build(),
+        );
+    }
+
+    for edge in desc.foreign_keys() {
+        builder = builder.add_block(foreign_key_atlas_hcl(
+            &desc.table_name,
+            &desc.schema_name,
+            edge,
+        ));
+    }
+
+    for index in &desc.indexes {
+        check_index(desc, index)?;
+        builder = builder.add_block(index_atlas_hcl(index));
+    }
+
+    Ok(builder.build())
+}
+
+/// The `schema` blocks and every table of `schema`, join tables included. MySQL enums are part
+/// of their columns, so there are no `enum` blocks.
+pub fn schema_hcl(schema: &Schema, charset: &Charset) -> Result<Body, UnsupportedError> {
+    let mut builder = Body::builder().add_blocks(schema.schemas().into_iter().map(|name| {
+        Block::builder("schema")
+            .add_label(name)
+            .add_attribute(("charset", charset.charset.clone()))
+            .add_attribute(("collate", charset.collate.clone()))
+            .build()
+    }));
+
+    for table in schema.tables() {
+        builder = builder.add_block(table_hcl(table, charset)?);
+    }
+
+    Ok(builder.build())
+}
+
+/// Literals as they are, expressions as MySQL needs them, see [`ddl::default_sql`].
+fn default_hcl(typ: &ColumnType, default: &ColumnDefault) -> Expression {
+    match default {
+        ColumnDefault::Expr(_) => FuncCall::builder("sql")
+            .arg(ddl::default_sql(typ, default))
+            .build()
+            .into(),
+        _ => default_atlas_expr(default),
+    }
+}
@@ -0,0 +1,234 @@
+//! Plain MySQL DDL. A MySQL schema is a database of its own, so tables aren't qualified and the
+//! statements run against the database the connection uses.
+
+use crate::descriptor::{
+    ColumnDefault, EdgeDescriptor, EntityDescriptor, EntityFieldDescriptor, IndexDescriptor,
+    IndexPart,
+};
+use crate::schema::Schema;
+use crate::sql::mysql::{check_index, Charset, ColumnType, UnsupportedError};
+use crate::sql::LogicalType;
+
+/// Quotes an identifier with backticks, doubling any backtick in it.
+pub fn quote_ident(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
+
+/// Quotes a string literal. MySQL reads backslashes as escapes, so they are doubled along with
+/// any `'`.
+pub fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+/// The `DEFAULT` of a column of type `typ`. MySQL needs expression defaults in parentheses,
+/// except for `CURRENT_TIMESTAMP`, which has to match the fractional seconds of its column.
+/// Text, blob and json columns only take expressions, so their literals are parenthesized too.
+pub fn default_sql(typ: &ColumnType, default: &ColumnDefault) -> String {
+    let literal = match default {
+        ColumnDefault::Bool(b) => b.to_string(),
+        ColumnDefault::Int(i) => i.to_string(),
+        ColumnDefault::Float(f) => f.clone(),
+        ColumnDefault::Str(s) => quote_literal(s),
+        ColumnDefault::Expr(e) => {
+            return match e.to_lowercase().as_str() {
+                "now()" | "current_timestamp" => match typ {
+                    ColumnType::DateTime(Some(precision))
+                    | ColumnType::Timestamp(Some(precision)) => {
+                        format!("CURRENT_TIMESTAMP({})", precision)
+                    }
+                    _ => "CURRENT_TIMESTAMP".to_string(),
+                },
+                _ => format!("({})", e),
+            }
+        }
+    };
+
+    match typ {
+        ColumnType::Text
+        | ColumnType::MediumText
+        | ColumnType::LongText
+        | ColumnType::Blob
+        | ColumnType::LongBlob
+        | ColumnType::Json => format!("({})", literal),
+        _ => literal,
+    }
+}
+
+/// A column definition. Serial columns are `AUTO_INCREMENT`.
+pub fn column_sql(
+    table: &EntityDescriptor,
+    field: &EntityFieldDescriptor,
+) -> Result<String, UnsupportedError> {
+    let typ = ColumnType::for_field(table, field)?;
+    let mut sql = format!("{} {}", quote_ident(&field.column_name), typ);
+
+    if !field.nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &field.default {
+        sql.push_str(" DEFAULT ");
+        sql.push_str(&default_sql(&typ, default));
+    }
+    if matches!(
+        field.sql_type,
+        LogicalType::SmallSerial | LogicalType::Serial | LogicalType::BigSerial
+    ) {
+        sql.push_str(" AUTO_INCREMENT");
+    }
+
+    Ok(sql)
+}
+
+/// `CREATE TABLE` with the columns and primary key, indexes and foreign keys are separate
+/// statements.
+pub fn create_table(
+    desc: &EntityDescriptor,
+    charset: &Charset,
+) -> Result<String, UnsupportedError> {
+    let mut lines = desc
+        .fields
+        .iter()
+        .map(|f| column_sql(desc, f))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !desc.primary_key.is_empty() {
+        lines.push(format!("PRIMARY KEY ({})", column_list(&desc.primary_key)));
+    }
+
+    Ok(format!(
+        "CREATE TABLE {} (\n  {}\n) DEFAULT CHARSET={} COLLATE={};",
+        quote_ident(&desc.table_name),
+        lines.join(",\n  "),
+        charset.charset,
+        charset.collate
+    ))
+}
+
+/// `CREATE INDEX`, expressions become functional key parts.
+pub fn create_index(
+    table: &EntityDescriptor,
+    index: &IndexDescriptor,
+) -> Result<String, UnsupportedError> {
+    check_index(table, index)?;
+
+    let parts = index
+        .parts
+        .iter()
+        .map(|part| match part {
+            IndexPart::Column(col) => quote_ident(col),
+            IndexPart::Expr(expr) => format!("({})", expr),
+        })
+        .collect::<Vec<_>>();
+
+    let mut sql = format!(
+        "CREATE {}INDEX {} ON {} ({})",
+        if index.unique { "UNIQUE " } else { "" },
+        quote_ident(&index.name),
+        quote_ident(&table.table_name),
+        parts.join(", ")
+    );
+
+    if let Some(method) = index.method {
+        sql.push_str(&format!(" USING {}", method.to_string().to_uppercase()));
+    }
+
+    sql.push(';');
+    Ok(sql)
+}
+
+/// The foreign key constraint of a non-inverse edge.
+pub fn add_foreign_key(table: &EntityDescriptor, edge: &EdgeDescriptor) -> String {
+    let mut sql = format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+        quote_ident(&table.table_name),
+        quote_ident(&edge.foreign_key_name(&table.table_name)),
+        column_list(&edge.columns),
+        quote_ident(&edge.target_table),
+        column_list(&edge.ref_columns)
+    );
+
+    if let Some(action) = edge.on_update {
+        sql.push_str(&format!(" ON UPDATE {}", action));
+    }
+    if let Some(action) = edge.on_delete {
+        sql.push_str(&format!(" ON DELETE {}", action));
+    }
+
+    sql.push(';');
+    sql
+}
+
+/// Every statement needed to create `schema` from scratch: tables, indexes and finally foreign
+/// keys, so tables can reference each other in any order.
+pub fn schema_statements(
+    schema: &Schema,
+    charset: &Charset,
+) -> Result<Vec<String>, UnsupportedError> {
+    let tables = schema.tables_by_dependency();
+
+    let mut statements = tables
+        .iter()
+        .map(|t| create_table(t, charset))
+        .collect::<Result<Vec<_>, _>>()?;
+    for table in &tables {
+        for index in &table.indexes {
+            statements.push(create_index(table, index)?);
+        }
+    }
+    for table in &tables {
+        statements.extend(table.foreign_keys().map(|e| add_foreign_key(table, e)));
+    }
+
+    Ok(statements)
+}
+
+fn column_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_sql() {
+        let int = ColumnType::Int { unsigned: false };
+        assert_eq!(
+            default_sql(&ColumnType::Bool, &ColumnDefault::Bool(true)),
+            "true"
+        );
+        assert_eq!(default_sql(&int, &ColumnDefault::Int(-1)), "-1");
+        assert_eq!(
+            default_sql(
+                &ColumnType::VarChar(8),
+                &ColumnDefault::Str("o'k\\".to_string())
+            ),
+            "'o''k\\\\'"
+        );
+        assert_eq!(
+            default_sql(
+                &ColumnType::LongText,
+                &ColumnDefault::Str("inbox".to_string())
+            ),
+            "('inbox')"
+        );
+        assert_eq!(
+            default_sql(
+                &ColumnType::Timestamp(Some(6)),
+                &ColumnDefault::Expr("now()".to_string())
+            ),
+            "CURRENT_TIMESTAMP(6)"
+        );
+        assert_eq!(
+            default_sql(
+                &ColumnType::Char(36),
+                &ColumnDefault::Expr("uuid()".to_string())
+            ),
+            "(uuid())"
+        );
+    }
+}
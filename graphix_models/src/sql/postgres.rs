@@ -1,9 +1,15 @@
+use crate::descriptor::{
+    ColumnDefault, EntityDescriptor, EntityFieldDescriptor, EnumDescriptor, Unsigned,
+};
+use crate::entity::traversal;
+use crate::schema::Schema;
+use crate::sql::Dialect;
+use hcl::expr::{Expression, FuncCall};
+use hcl::{Block, Body, TraversalOperator, Variable};
 use quote::{quote, ToTokens};
 use std::fmt::Formatter;
-use syn::spanned::Spanned;
-use syn::Type;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ColumnType {
     Array(Box<ColumnType>),
     Bit(Option<usize>),
@@ -63,6 +69,171 @@ pub enum ColumnType {
     Uuid,
     Xml,
 }
+impl ColumnType {
+    /// The expression Atlas HCL uses for this type in a table of `schema`, e.g. `varchar(255)`,
+    /// `enum.status` or `sql("integer[]")`. Arrays are spelled in SQL, with enums qualified by
+    /// the schema `enums` declares them in and domains by `schema`.
+    pub fn as_atlas_expr(&self, schema: &str, enums: &[EnumDescriptor]) -> Expression {
+        let name = self.to_string();
+
+        match self {
+            Self::Array(_) => FuncCall::builder("sql")
+                .arg(self.sql_name(schema, enums))
+                .build()
+                .into(),
+            Self::Domain(_) | Self::Enum(_) => traversal(&name),
+            _ => match name.split_once('(') {
+                Some((func, args)) => args
+                    .trim_end_matches(')')
+                    .split(',')
+                    .fold(FuncCall::builder(func), |call, arg| {
+                        call.arg(arg.parse::<u64>().expect("type arguments are integers"))
+                    })
+                    .build()
+                    .into(),
+                None => Variable::new(name)
+                    .expect("type names are valid identifiers")
+                    .into(),
+            },
+        }
+    }
+
+    /// The SQL spelling of this type, user defined types as quoted schema qualified names.
+    fn sql_name(&self, schema: &str, enums: &[EnumDescriptor]) -> String {
+        let qualified = |schema: &str, name: &str| {
+            let schema = schema.strip_prefix("schema.").unwrap_or(schema);
+            format!("\"{}\".\"{}\"", schema, name)
+        };
+
+        match self {
+            Self::Array(of) => format!("{}[]", of.sql_name(schema, enums)),
+            Self::Enum(name) => {
+                let schema = enums
+                    .iter()
+                    .find(|e| e.name == *name)
+                    .map_or(schema, |e| e.schema_name.as_str());
+                qualified(schema, name)
+            }
+            Self::Domain(name) => qualified(schema, name),
+            other => other.to_string(),
+        }
+    }
+
+    /// Parses the type of an Atlas HCL column, the inverse of [`ColumnType::as_atlas_expr`].
+    /// Atlas spells multi-word types with underscores, e.g. `character_varying(255)`.
+    pub fn from_atlas_expr(expr: &Expression) -> Result<Self, ParseColumnTypeError> {
+        let name = match expr {
+            Expression::Variable(v) => v.as_str().replace('_', " "),
+            Expression::Traversal(t) => {
+                let Expression::Variable(root) = &t.expr else {
+                    return Err(ParseColumnTypeError::new(&expr.to_string(), "unknown type"));
+                };
+                t.operators.iter().fold(root.to_string(), |path, op| match op {
+                    TraversalOperator::GetAttr(attr) => format!("{}.{}", path, attr),
+                    _ => path,
+                })
+            }
+            Expression::FuncCall(call) => match (call.name.as_str(), call.args.as_slice()) {
+                ("sql", [Expression::String(sql)]) => return parse_sql_name(sql),
+                (func, args) => format!(
+                    "{}({})",
+                    func.replace('_', " "),
+                    args.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(",")
+                ),
+            },
+            Expression::String(sql) => sql.clone(),
+            _ => return Err(ParseColumnTypeError::new(&expr.to_string(), "unknown type")),
+        };
+
+        name.parse()
+    }
+}
+
+/// Parses a type spelled in SQL, a quoted schema qualified name is an enum.
+fn parse_sql_name(sql: &str) -> Result<ColumnType, ParseColumnTypeError> {
+    if let Some(of) = sql.trim().strip_suffix("[]") {
+        return Ok(ColumnType::Array(Box::new(parse_sql_name(of)?)));
+    }
+
+    match sql.trim().rsplit_once("\".\"") {
+        Some((_, name)) if sql.trim().starts_with('"') => Ok(ColumnType::Enum(
+            name.trim_end_matches('"').replace("\"\"", "\""),
+        )),
+        _ => sql.parse(),
+    }
+}
+
+impl ColumnType {
+    /// Whether `default` is a valid `DEFAULT` for a column of this type. Expressions are only
+    /// checked by the database.
+    pub fn accepts_default(&self, default: &ColumnDefault) -> bool {
+        match default {
+            ColumnDefault::Expr(_) => true,
+            ColumnDefault::Bool(_) => matches!(self, Self::Boolean),
+            ColumnDefault::Int(i) => match self {
+                Self::SmallInt | Self::SmallSerial => i16::try_from(*i).is_ok(),
+                Self::Integer | Self::Serial => i32::try_from(*i).is_ok(),
+                _ => self.is_numeric(),
+            },
+            ColumnDefault::Float(_) => matches!(
+                self,
+                Self::Numeric(_) | Self::Real | Self::DoublePrecision | Self::Float(_) | Self::Money
+            ),
+            ColumnDefault::Str(s) => match self {
+                Self::Char(Some(len)) | Self::VarChar(Some(len)) => s.chars().count() <= *len,
+                _ => !self.is_numeric() && !matches!(self, Self::Boolean),
+            },
+        }
+    }
+
+    /// The type of a column referencing a column of this type, serials become the integer they
+    /// are backed by.
+    pub fn as_reference(&self) -> ColumnType {
+        match self {
+            Self::SmallSerial => Self::SmallInt,
+            Self::Serial => Self::Integer,
+            Self::BigSerial => Self::BigInt,
+            other => other.clone(),
+        }
+    }
+
+    /// Whether `LIKE` applies to columns of this type.
+    pub fn is_text(&self) -> bool {
+        matches!(self, Self::VarChar(_) | Self::Char(_) | Self::Text)
+    }
+
+    /// Whether values of this type have an order, numbers, dates and times.
+    pub fn is_ordered(&self) -> bool {
+        self.is_numeric()
+            || matches!(
+                self,
+                Self::Date
+                    | Self::Time
+                    | Self::TimeTz
+                    | Self::Timestamp(_)
+                    | Self::TimestampTz
+                    | Self::Interval
+            )
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::SmallInt
+                | Self::Integer
+                | Self::Int
+                | Self::BigInt
+                | Self::SmallSerial
+                | Self::Serial
+                | Self::BigSerial
+                | Self::Numeric(_)
+                | Self::Real
+                | Self::DoublePrecision
+                | Self::Float(_)
+                | Self::Money
+        )
+    }
+}
 impl std::fmt::Display for ColumnType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -151,63 +322,596 @@ impl std::fmt::Display for ColumnType {
         }
     }
 }
-impl TryFrom<&syn::Type> for ColumnType {
-    type Error = syn::Error;
-
-    fn try_from(value: &Type) -> Result<Self, Self::Error> {
-        let type_string = value.to_token_stream().to_string();
-
-        match value {
-            syn::Type::Path(tp) => {
-                let ident = tp.path.require_ident()?;
-
-                match ident.to_string().as_str() {
-                    "String" => Ok(Self::Text),
-                    "i8" => Ok(Self::SmallInt),
-                    "i16" => Ok(Self::SmallInt),
-                    "i32" => Ok(Self::Integer),
-                    "i64" => Ok(Self::BigInt),
-                    "isize" => Ok(Self::BigInt),
-                    "u8" => Ok(Self::SmallInt),
-                    "u16" => Ok(Self::SmallInt),
-                    "u32" => Ok(Self::Integer),
-                    "u64" => Ok(Self::BigInt),
-                    "usize" => Ok(Self::BigInt),
-                    "f32" => Ok(Self::Real),
-                    "f64" => Ok(Self::DoublePrecision),
-                    "bool" => Ok(Self::Boolean),
-                    "char" => Ok(Self::Char(Some(1))),
-                    // TODO: handle compound types
-                    // TODO: handle nonstd types
-                    _ => Err(syn::Error::new_spanned(
-                        ident,
-                        format!(
-                            "graphix is currently unable to handle type: {}",
-                            type_string,
-                        ),
-                    )),
+/// Error returned when a string cannot be parsed into a [`ColumnType`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColumnTypeError {
+    input: String,
+    reason: String,
+}
+impl ParseColumnTypeError {
+    fn new(input: &str, reason: impl Into<String>) -> Self {
+        Self {
+            input: input.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+impl std::fmt::Display for ParseColumnTypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid postgres type `{}`: {}", self.input, self.reason)
+    }
+}
+impl std::error::Error for ParseColumnTypeError {}
+
+/// Column types serialize to their SQL spelling, e.g. `"varchar(255)"`, which keeps snapshots
+/// readable.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ColumnType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ColumnType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl std::str::FromStr for ColumnType {
+    type Err = ParseColumnTypeError;
+
+    /// Parses the output of `Display` back into a `ColumnType`, along with the common postgres
+    /// spellings and aliases (`int4`, `character varying(n)`, `timestamp with time zone`, ...).
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // only keywords are case insensitive, ASCII lowercasing keeps the offsets of `trimmed`
+        // valid for the names of enums and domains
+        let trimmed = input.trim();
+        let normalized = trimmed.to_ascii_lowercase();
+        if normalized.is_empty() {
+            return Err(ParseColumnTypeError::new(input, "type cannot be empty"));
+        }
+
+        // `integer[]`, `text[][]` and `integer[3]` are all arrays, postgres ignores the bounds
+        if let Some(elem) = normalized.strip_suffix(']') {
+            let Some(open) = elem.rfind('[') else {
+                return Err(ParseColumnTypeError::new(input, "unbalanced `]`"));
+            };
+            let bound = elem[open + 1..].trim();
+            if !bound.is_empty() && bound.parse::<usize>().is_err() {
+                return Err(ParseColumnTypeError::new(input, "invalid array bound"));
+            }
+
+            return Ok(Self::Array(Box::new(trimmed[..open].parse()?)));
+        }
+
+        let (name, args) = split_type_args(input, &normalized)?;
+
+        let no_args = |typ: ColumnType| match args {
+            None => Ok(typ),
+            Some(_) => Err(ParseColumnTypeError::new(
+                input,
+                format!("`{}` does not take arguments", name),
+            )),
+        };
+        let single_arg = || -> Result<Option<usize>, ParseColumnTypeError> {
+            match &args {
+                None => Ok(None),
+                Some(args) if args.len() == 1 => Ok(Some(args[0])),
+                Some(_) => Err(ParseColumnTypeError::new(
+                    input,
+                    format!("`{}` takes a single argument", name),
+                )),
+            }
+        };
+
+        let (written_name, _) = split_type_args(input, trimmed)?;
+        if name.starts_with("domain.") {
+            return no_args(Self::Domain(written_name["domain.".len()..].to_string()));
+        }
+        if name.starts_with("enum.") {
+            return no_args(Self::Enum(written_name["enum.".len()..].to_string()));
+        }
+
+        match name.as_str() {
+            "bit" => Ok(Self::Bit(single_arg()?)),
+            "bit_varying" | "bit varying" | "varbit" => Ok(Self::BitVarying(single_arg()?)),
+            "boolean" | "bool" => no_args(Self::Boolean),
+            "bytea" => no_args(Self::ByteA),
+            "date" => no_args(Self::Date),
+            "time" | "time without time zone" => no_args(Self::Time),
+            "timetz" | "time with time zone" => no_args(Self::TimeTz),
+            "timestamp" | "timestamp without time zone" => Ok(Self::Timestamp(single_arg()?)),
+            "timestamptz" | "timestamp with time zone" => no_args(Self::TimestampTz),
+            "interval" => no_args(Self::Interval),
+            "numeric" | "decimal" => Ok(Self::Numeric(args)),
+            "real" | "float4" => no_args(Self::Real),
+            "double_precision" | "double precision" | "float8" => no_args(Self::DoublePrecision),
+            "float" => match single_arg()? {
+                Some(precision) => Ok(Self::Float(precision)),
+                None => Ok(Self::DoublePrecision),
+            },
+            "circle" => no_args(Self::Circle),
+            "line" => no_args(Self::Line),
+            "lseg" => no_args(Self::LSeg),
+            "box" => no_args(Self::Box),
+            "path" => no_args(Self::Path),
+            "polygon" => no_args(Self::Polygon),
+            "point" => no_args(Self::Point),
+            "smallint" | "int2" => no_args(Self::SmallInt),
+            "integer" | "int4" => no_args(Self::Integer),
+            "int" => no_args(Self::Int),
+            "bigint" | "int8" => no_args(Self::BigInt),
+            "json" => no_args(Self::Json),
+            "jsonb" => no_args(Self::Jsonb),
+            "money" => no_args(Self::Money),
+            "inet" => no_args(Self::INet),
+            "cidr" => no_args(Self::Cidr),
+            "macaddr" => no_args(Self::MacAddr),
+            "macaddr8" => no_args(Self::MacAddr8),
+            "int4range" => no_args(Self::Int4Range),
+            "int8range" => no_args(Self::Int8Range),
+            "numrange" => no_args(Self::NumRange),
+            "tsrange" => no_args(Self::TsRange),
+            "tstzrange" => no_args(Self::TsTzRange),
+            "daterange" => no_args(Self::DateRange),
+            "int4multirange" => no_args(Self::Int4MultiRange),
+            "int8multirange" => no_args(Self::Int8MultiRange),
+            "nummultirange" => no_args(Self::NumMultiRange),
+            "tsmultirange" => no_args(Self::TsMultiRange),
+            "tstzmultirange" => no_args(Self::TsTzMultiRange),
+            "datemultirange" => no_args(Self::DateMultiRange),
+            "smallserial" | "serial2" => no_args(Self::SmallSerial),
+            "serial" | "serial4" => no_args(Self::Serial),
+            "bigserial" | "serial8" => no_args(Self::BigSerial),
+            "varchar" | "character varying" => Ok(Self::VarChar(single_arg()?)),
+            "char" | "character" | "bpchar" => Ok(Self::Char(single_arg()?)),
+            "text" => no_args(Self::Text),
+            "tsvector" => no_args(Self::TsVector),
+            "tsquery" => no_args(Self::TsQuery),
+            "uuid" => no_args(Self::Uuid),
+            "xml" => no_args(Self::Xml),
+            _ => Err(ParseColumnTypeError::new(input, "unknown type")),
+        }
+    }
+}
+
+/// Splits a normalized type such as `timestamp(3) without time zone` into its name
+/// (`timestamp without time zone`) and its parenthesized arguments (`[3]`).
+fn split_type_args(
+    input: &str,
+    normalized: &str,
+) -> Result<(String, Option<Vec<usize>>), ParseColumnTypeError> {
+    let (name, args) = match normalized.find('(') {
+        Some(open) => {
+            let Some(close) = normalized[open..].find(')').map(|c| c + open) else {
+                return Err(ParseColumnTypeError::new(input, "unbalanced `(`"));
+            };
+
+            let args = normalized[open + 1..close]
+                .split(',')
+                .map(|a| a.trim().parse::<usize>())
+                .collect::<Result<Vec<usize>, _>>()
+                .map_err(|_| ParseColumnTypeError::new(input, "arguments must be integers"))?;
+
+            (
+                format!("{} {}", &normalized[..open], &normalized[close + 1..]),
+                Some(args),
+            )
+        }
+        None => (normalized.to_string(), None),
+    };
+
+    if name.contains(['(', ')']) {
+        return Err(ParseColumnTypeError::new(input, "unexpected parenthesis"));
+    }
+
+    Ok((name.split_whitespace().collect::<Vec<_>>().join(" "), args))
+}
+
+/// A Rust type that can be stored in a postgres column.
+///
+/// `#[derive(Entity)]` resolves the column type of every field through this trait, so any type,
+/// including ones defined outside of graphix, can be used as a field by implementing it.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a graphix column",
+    note = "implement `graphix::sql::postgres::SqlType` for it, or set `#[graphix(sql_type = \"...\")]` on the field"
+)]
+pub trait SqlType {
+    /// Whether a column of this type accepts `NULL`.
+    const NULLABLE: bool = false;
+
+    /// The width of this type if it's an unsigned integer, kept unsigned by other dialects.
+    const UNSIGNED: Option<Unsigned> = None;
+
+    /// The postgres type of a column holding this type.
+    fn column_type() -> ColumnType;
+
+    /// The postgres type of a column holding a `Vec` or array of this type.
+    fn array_column_type() -> ColumnType {
+        ColumnType::Array(Box::new(Self::column_type()))
+    }
+
+    /// The postgres enum a column of this type depends on, if any.
+    fn enum_descriptor() -> Option<EnumDescriptor> {
+        None
+    }
+}
+
+/// A fieldless Rust enum stored as a postgres enum, usually implemented with
+/// `#[derive(SqlEnum)]`.
+pub trait SqlEnum: SqlType + Sized {
+    /// The labels of the postgres enum, in declaration order.
+    const VALUES: &'static [&'static str];
+
+    /// The label this value is stored as.
+    fn as_label(&self) -> &'static str;
+
+    /// The value stored as `label`, if there is one.
+    fn from_label(label: &str) -> Option<Self>;
+}
+
+/// Marker types for the kinds of literal `#[graphix(default = ...)]` accepts.
+pub mod literal {
+    pub struct Bool;
+    pub struct Int;
+    pub struct Float;
+    pub struct Str;
+}
+
+/// Implemented by column types that can default to a literal of kind `L`, used by
+/// `#[derive(Entity)]` to type-check `#[graphix(default = ...)]`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` columns cannot default to a literal of this kind",
+    note = "use `#[graphix(default_expr = \"...\")]` for anything the literal cannot express"
+)]
+pub trait AcceptsDefault<L> {
+    /// The only strings a `literal::Str` default can be, the labels of an enum.
+    const LABELS: Option<&'static [&'static str]> = None;
+
+    /// The most characters a `literal::Str` default can have.
+    const MAX_CHARS: Option<usize> = None;
+
+    /// The smallest `literal::Int` default.
+    const MIN: Option<i128> = None;
+
+    /// The largest `literal::Int` default.
+    const MAX: Option<i128> = None;
+}
+
+/// Whether `i` is a valid integer default for a `T` column, evaluated at compile time by
+/// `#[derive(Entity)]`.
+pub const fn accepts_int_default<T: AcceptsDefault<literal::Int> + ?Sized>(i: i128) -> bool {
+    let above_min = match T::MIN {
+        Some(min) => i >= min,
+        None => true,
+    };
+    let below_max = match T::MAX {
+        Some(max) => i <= max,
+        None => true,
+    };
+    above_min && below_max
+}
+
+/// Whether `s` is a valid string default for a `T` column, evaluated at compile time by
+/// `#[derive(Entity)]`.
+pub const fn accepts_str_default<T: AcceptsDefault<literal::Str> + ?Sized>(s: &str) -> bool {
+    if let Some(labels) = T::LABELS {
+        let mut i = 0;
+        while i < labels.len() {
+            if str_eq(labels[i], s) {
+                return true;
+            }
+            i += 1;
+        }
+        return false;
+    }
+
+    match T::MAX_CHARS {
+        Some(max) => char_count(s) <= max,
+        None => true,
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn char_count(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let (mut count, mut i) = (0, 0);
+    while i < bytes.len() {
+        // every char has exactly one byte that isn't a utf-8 continuation byte
+        if bytes[i] & 0xC0 != 0x80 {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+macro_rules! impl_sql_type {
+    ($($typ:ty [$($literal:ident),*] => $column_type:expr),* $(,)?) => {
+        $(
+            impl SqlType for $typ {
+                fn column_type() -> ColumnType {
+                    $column_type
+                }
+            }
+
+            $(impl AcceptsDefault<literal::$literal> for $typ {})*
+        )*
+    };
+}
+
+mod external;
+pub mod ddl;
+
+impl_sql_type! {
+    String [Str] => ColumnType::Text,
+    i8 [] => ColumnType::SmallInt,
+    i16 [] => ColumnType::SmallInt,
+    i32 [] => ColumnType::Integer,
+    i64 [] => ColumnType::BigInt,
+    isize [] => ColumnType::BigInt,
+    f32 [Int, Float] => ColumnType::Real,
+    f64 [Int, Float] => ColumnType::DoublePrecision,
+    bool [Bool] => ColumnType::Boolean,
+    char [] => ColumnType::Char(Some(1)),
+}
+
+macro_rules! impl_sql_type_unsigned {
+    ($($typ:ty [$unsigned:ident] => $column_type:expr),* $(,)?) => {
+        $(
+            impl SqlType for $typ {
+                const UNSIGNED: Option<Unsigned> = Some(Unsigned::$unsigned);
+
+                fn column_type() -> ColumnType {
+                    $column_type
                 }
             }
+        )*
+    };
+}
+
+// stored bit for bit in the signed column of the same width
+impl_sql_type_unsigned! {
+    u16 [U16] => ColumnType::SmallInt,
+    u32 [U32] => ColumnType::Integer,
+    u64 [U64] => ColumnType::BigInt,
+    usize [U64] => ColumnType::BigInt,
+}
+
+macro_rules! impl_int_default {
+    ($($typ:ty => $min:expr, $max:expr),* $(,)?) => {
+        $(
+            impl AcceptsDefault<literal::Int> for $typ {
+                const MIN: Option<i128> = Some($min as i128);
+                const MAX: Option<i128> = Some($max as i128);
+            }
+        )*
+    };
+}
+
+impl_int_default! {
+    i8 => i8::MIN, i8::MAX,
+    i16 => i16::MIN, i16::MAX,
+    i32 => i32::MIN, i32::MAX,
+    i64 => i64::MIN, i64::MAX,
+    isize => i64::MIN, i64::MAX,
+    // defaults are written as is, so they have to fit the signed column too
+    u8 => 0, u8::MAX,
+    u16 => 0, i16::MAX,
+    u32 => 0, i32::MAX,
+    u64 => 0, i64::MAX,
+    usize => 0, i64::MAX,
+}
+
+impl AcceptsDefault<literal::Str> for char {
+    const MAX_CHARS: Option<usize> = Some(1);
+}
+
+impl SqlType for u8 {
+    const UNSIGNED: Option<Unsigned> = Some(Unsigned::U8);
+
+    fn column_type() -> ColumnType {
+        ColumnType::SmallInt
+    }
+
+    // `Vec<u8>` and `[u8; N]` are raw bytes rather than an array of numbers
+    fn array_column_type() -> ColumnType {
+        ColumnType::ByteA
+    }
+}
+
+impl<L, T: AcceptsDefault<L>> AcceptsDefault<L> for Option<T> {
+    const LABELS: Option<&'static [&'static str]> = T::LABELS;
+    const MAX_CHARS: Option<usize> = T::MAX_CHARS;
+    const MIN: Option<i128> = T::MIN;
+    const MAX: Option<i128> = T::MAX;
+}
+
+impl<T: SqlType> SqlType for Option<T> {
+    const NULLABLE: bool = true;
+    const UNSIGNED: Option<Unsigned> = T::UNSIGNED;
+
+    fn column_type() -> ColumnType {
+        T::column_type()
+    }
+
+    fn enum_descriptor() -> Option<EnumDescriptor> {
+        T::enum_descriptor()
+    }
+}
+
+impl<T: SqlType> SqlType for Vec<T> {
+    fn column_type() -> ColumnType {
+        T::array_column_type()
+    }
+
+    fn enum_descriptor() -> Option<EnumDescriptor> {
+        T::enum_descriptor()
+    }
+}
+
+impl<T: SqlType, const N: usize> SqlType for [T; N] {
+    fn column_type() -> ColumnType {
+        T::array_column_type()
+    }
+
+    fn enum_descriptor() -> Option<EnumDescriptor> {
+        T::enum_descriptor()
+    }
+}
 
-            _ => Err(syn::Error::new_spanned(value, "invalid type")),
+/// Quotes an `Option<usize>` back into the tokens that construct it.
+fn quote_opt_usize(value: &Option<usize>) -> proc_macro2::TokenStream {
+    match value {
+        Some(v) => {
+            let v = proc_macro2::Literal::usize_unsuffixed(*v);
+            quote! { Some(#v) }
         }
+        None => quote! { None },
     }
 }
+
 impl ToTokens for ColumnType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let path = quote! { graphix::sql::postgres::ColumnType };
+
         tokens.extend(match self {
-            Self::Text => quote! { graphix::sql::postgres::ColumnType::Text },
-            Self::SmallInt => quote! { graphix::sql::postgres::ColumnType::SmallInt },
-            Self::Integer => quote! { graphix::sql::postgres::ColumnType::Integer },
-            Self::BigInt => quote! { graphix::sql::postgres::ColumnType::BigInt },
-            Self::Real => quote! { graphix::sql::postgres::ColumnType::Real },
-            Self::DoublePrecision => quote! { graphix::sql::postgres::ColumnType::DoublePrecision },
-            Self::Boolean => quote! { graphix::sql::postgres::ColumnType::Boolean },
-            _ => syn::Error::new(tokens.span(), format!("graphix does not yet support the column type {}", self)).to_compile_error()
+            Self::Array(of) => quote! { #path::Array(Box::new(#of)) },
+            Self::Bit(size) => {
+                let size = quote_opt_usize(size);
+                quote! { #path::Bit(#size) }
+            }
+            Self::BitVarying(size) => {
+                let size = quote_opt_usize(size);
+                quote! { #path::BitVarying(#size) }
+            }
+            Self::Timestamp(precision) => {
+                let precision = quote_opt_usize(precision);
+                quote! { #path::Timestamp(#precision) }
+            }
+            Self::Domain(name) => quote! { #path::Domain(#name.to_string()) },
+            Self::Enum(name) => quote! { #path::Enum(#name.to_string()) },
+            Self::Numeric(args) => match args {
+                Some(args) => {
+                    let args = args
+                        .iter()
+                        .map(|a| proc_macro2::Literal::usize_unsuffixed(*a));
+                    quote! { #path::Numeric(Some(vec![#(#args),*])) }
+                }
+                None => quote! { #path::Numeric(None) },
+            },
+            Self::Float(precision) => {
+                let precision = proc_macro2::Literal::usize_unsuffixed(*precision);
+                quote! { #path::Float(#precision) }
+            }
+            Self::VarChar(length) => {
+                let length = quote_opt_usize(length);
+                quote! { #path::VarChar(#length) }
+            }
+            Self::Char(length) => {
+                let length = quote_opt_usize(length);
+                quote! { #path::Char(#length) }
+            }
+            Self::Boolean => quote! { #path::Boolean },
+            Self::ByteA => quote! { #path::ByteA },
+            Self::Date => quote! { #path::Date },
+            Self::Time => quote! { #path::Time },
+            Self::TimeTz => quote! { #path::TimeTz },
+            Self::TimestampTz => quote! { #path::TimestampTz },
+            Self::Interval => quote! { #path::Interval },
+            Self::Real => quote! { #path::Real },
+            Self::DoublePrecision => quote! { #path::DoublePrecision },
+            Self::Circle => quote! { #path::Circle },
+            Self::Line => quote! { #path::Line },
+            Self::LSeg => quote! { #path::LSeg },
+            Self::Box => quote! { #path::Box },
+            Self::Path => quote! { #path::Path },
+            Self::Polygon => quote! { #path::Polygon },
+            Self::Point => quote! { #path::Point },
+            Self::SmallInt => quote! { #path::SmallInt },
+            Self::Integer => quote! { #path::Integer },
+            Self::Int => quote! { #path::Int },
+            Self::BigInt => quote! { #path::BigInt },
+            Self::Json => quote! { #path::Json },
+            Self::Jsonb => quote! { #path::Jsonb },
+            Self::Money => quote! { #path::Money },
+            Self::INet => quote! { #path::INet },
+            Self::Cidr => quote! { #path::Cidr },
+            Self::MacAddr => quote! { #path::MacAddr },
+            Self::MacAddr8 => quote! { #path::MacAddr8 },
+            Self::Int4Range => quote! { #path::Int4Range },
+            Self::Int8Range => quote! { #path::Int8Range },
+            Self::NumRange => quote! { #path::NumRange },
+            Self::TsRange => quote! { #path::TsRange },
+            Self::TsTzRange => quote! { #path::TsTzRange },
+            Self::DateRange => quote! { #path::DateRange },
+            Self::Int4MultiRange => quote! { #path::Int4MultiRange },
+            Self::Int8MultiRange => quote! { #path::Int8MultiRange },
+            Self::NumMultiRange => quote! { #path::NumMultiRange },
+            Self::TsMultiRange => quote! { #path::TsMultiRange },
+            Self::TsTzMultiRange => quote! { #path::TsTzMultiRange },
+            Self::DateMultiRange => quote! { #path::DateMultiRange },
+            Self::SmallSerial => quote! { #path::SmallSerial },
+            Self::Serial => quote! { #path::Serial },
+            Self::BigSerial => quote! { #path::BigSerial },
+            Self::Text => quote! { #path::Text },
+            Self::TsVector => quote! { #path::TsVector },
+            Self::TsQuery => quote! { #path::TsQuery },
+            Self::Uuid => quote! { #path::Uuid },
+            Self::Xml => quote! { #path::Xml },
         });
     }
 }
 
+/// Postgres, the dialect descriptors are closest to. It can express every logical type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Postgres;
+impl Dialect for Postgres {
+    type ColumnType = ColumnType;
+    type Error = std::convert::Infallible;
+
+    fn column_type(
+        &self,
+        _table: &EntityDescriptor,
+        field: &EntityFieldDescriptor,
+    ) -> Result<ColumnType, Self::Error> {
+        Ok(ColumnType::from(&field.sql_type))
+    }
+
+    fn quote_ident(&self, ident: &str) -> String {
+        ddl::quote_ident(ident)
+    }
+
+    fn table_hcl(&self, table: &EntityDescriptor) -> Result<Block, Self::Error> {
+        Ok(table.as_atlas_hcl())
+    }
+
+    fn schema_hcl(&self, schema: &Schema) -> Result<Body, Self::Error> {
+        Ok(schema.as_atlas_hcl())
+    }
+
+    fn schema_statements(&self, schema: &Schema) -> Result<Vec<String>, Self::Error> {
+        Ok(ddl::schema_statements(schema))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -218,6 +922,273 @@ mod test {
             ColumnType::Array(Box::new(ColumnType::Integer)).to_string(),
             "integer[]"
         );
+        assert_eq!(
+            ColumnType::Array(Box::new(ColumnType::Array(Box::new(ColumnType::Text)))).to_string(),
+            "text[][]"
+        );
+    }
+
+    fn nullable<T: SqlType>() -> bool {
+        T::NULLABLE
+    }
+
+    #[test]
+    fn test_sql_type() {
+        assert_eq!(String::column_type().to_string(), "text");
+        assert_eq!(u8::column_type().to_string(), "smallint");
+        assert_eq!(i64::column_type().to_string(), "bigint");
+        assert_eq!(char::column_type().to_string(), "char(1)");
+        assert!(!nullable::<i32>());
+        assert!(nullable::<Option<i32>>());
+        assert_eq!(<Option<bool>>::column_type().to_string(), "boolean");
+    }
+
+    #[test]
+    fn test_sql_type_array() {
+        assert_eq!(<Vec<i32>>::column_type().to_string(), "integer[]");
+        assert_eq!(<Vec<String>>::column_type().to_string(), "text[]");
+        assert_eq!(<[f64; 3]>::column_type().to_string(), "double_precision[]");
+        assert_eq!(<Vec<Vec<i64>>>::column_type().to_string(), "bigint[][]");
+        assert_eq!(<Vec<[bool; 2]>>::column_type().to_string(), "boolean[][]");
+        assert_eq!(<Vec<Option<i32>>>::column_type().to_string(), "integer[]");
+    }
+
+    #[test]
+    fn test_sql_type_bytea() {
+        assert_eq!(<Vec<u8>>::column_type().to_string(), "bytea");
+        assert_eq!(<[u8; 32]>::column_type().to_string(), "bytea");
+        assert_eq!(<Vec<Vec<u8>>>::column_type().to_string(), "bytea[]");
+    }
+
+    #[test]
+    fn test_as_atlas_expr() {
+        let cases = [
+            (ColumnType::Integer, "integer"),
+            (ColumnType::VarChar(Some(255)), "varchar(255)"),
+            (ColumnType::Enum("status".to_string()), "enum.status"),
+            (
+                ColumnType::Array(Box::new(ColumnType::Text)),
+                "sql(\"text[]\")",
+            ),
+            (
+                ColumnType::Array(Box::new(ColumnType::Enum("status".to_string()))),
+                "sql(\"\\\"public\\\".\\\"status\\\"[]\")",
+            ),
+        ];
+
+        for (typ, expected) in cases {
+            let body = hcl::Body::builder()
+                .add_attribute(("type", typ.as_atlas_expr("schema.public", &[])))
+                .build();
+            assert_eq!(hcl::to_string(&body).unwrap().trim(), format!("type = {}", expected));
+        }
+    }
+
+    #[test]
+    fn test_from_atlas_expr() {
+        for typ in [
+            ColumnType::Integer,
+            ColumnType::DoublePrecision,
+            ColumnType::VarChar(Some(255)),
+            ColumnType::Numeric(Some(vec![10, 2])),
+            ColumnType::Enum("status".to_string()),
+            ColumnType::Array(Box::new(ColumnType::Text)),
+            ColumnType::Array(Box::new(ColumnType::Enum("status".to_string()))),
+        ] {
+            let expr = typ.as_atlas_expr("schema.public", &[]);
+            assert_eq!(ColumnType::from_atlas_expr(&expr).unwrap(), typ);
+        }
+
+        // atlas inspects varchar columns as `character_varying`
+        let body: hcl::Body = hcl::parse("type = character_varying(64)").unwrap();
+        let expr = &body.attributes().next().unwrap().expr;
+        assert_eq!(
+            ColumnType::from_atlas_expr(expr).unwrap(),
+            ColumnType::VarChar(Some(64))
+        );
+        assert!(ColumnType::from_atlas_expr(&Expression::Bool(true)).is_err());
+    }
+
+    #[test]
+    fn test_accepts_default() {
+        let int = ColumnDefault::Int(1);
+        let float = ColumnDefault::Float("1.5".to_string());
+        let text = ColumnDefault::Str("pending".to_string());
+        let expr = ColumnDefault::Expr("now()".to_string());
+
+        assert!(ColumnType::Boolean.accepts_default(&ColumnDefault::Bool(false)));
+        assert!(!ColumnType::Integer.accepts_default(&ColumnDefault::Bool(false)));
+        assert!(ColumnType::BigInt.accepts_default(&int));
+        assert!(!ColumnType::SmallInt.accepts_default(&ColumnDefault::Int(100000)));
+        assert!(ColumnType::Numeric(None).accepts_default(&float));
+        assert!(!ColumnType::Integer.accepts_default(&float));
+        assert!(!ColumnType::Integer.accepts_default(&text));
+        assert!(ColumnType::Text.accepts_default(&text));
+        assert!(ColumnType::Enum("status".to_string()).accepts_default(&text));
+        assert!(ColumnType::VarChar(Some(7)).accepts_default(&text));
+        assert!(!ColumnType::VarChar(Some(6)).accepts_default(&text));
+        assert!(!ColumnType::Text.accepts_default(&int));
+        assert!(ColumnType::TimestampTz.accepts_default(&expr));
+    }
+
+    #[test]
+    fn test_accepts_str_default() {
+        struct Status;
+        impl AcceptsDefault<literal::Str> for Status {
+            const LABELS: Option<&'static [&'static str]> = Some(&["active", "banned"]);
+        }
+
+        assert!(accepts_str_default::<String>("anything"));
+        assert!(accepts_str_default::<Status>("banned"));
+        assert!(accepts_str_default::<Option<Status>>("active"));
+        assert!(!accepts_str_default::<Status>("bann"));
+        assert!(!accepts_str_default::<Status>("bogus"));
+        assert!(accepts_str_default::<char>("é"));
+        assert!(!accepts_str_default::<char>("ab"));
+    }
+
+    #[test]
+    fn test_accepts_int_default() {
+        assert!(accepts_int_default::<i16>(-32768));
+        assert!(!accepts_int_default::<i16>(100000));
+        assert!(accepts_int_default::<Option<u8>>(255));
+        assert!(!accepts_int_default::<u8>(-1));
+        assert!(!accepts_int_default::<u16>(40000));
+        assert!(accepts_int_default::<f32>(100000));
+    }
+
+    #[test]
+    fn test_to_tokens() {
+        let cases = [
+            (ColumnType::Text, "graphix :: sql :: postgres :: ColumnType :: Text"),
+            (
+                ColumnType::VarChar(Some(255)),
+                "graphix :: sql :: postgres :: ColumnType :: VarChar (Some (255))",
+            ),
+            (
+                ColumnType::Timestamp(None),
+                "graphix :: sql :: postgres :: ColumnType :: Timestamp (None)",
+            ),
+            (
+                ColumnType::Numeric(Some(vec![12, 2])),
+                "graphix :: sql :: postgres :: ColumnType :: Numeric (Some (vec ! [12 , 2]))",
+            ),
+            (
+                ColumnType::Float(24),
+                "graphix :: sql :: postgres :: ColumnType :: Float (24)",
+            ),
+            (
+                ColumnType::Enum("status".to_string()),
+                "graphix :: sql :: postgres :: ColumnType :: Enum (\"status\" . to_string ())",
+            ),
+            (
+                ColumnType::Array(Box::new(ColumnType::Uuid)),
+                "graphix :: sql :: postgres :: ColumnType :: Array (Box :: new (graphix :: sql :: postgres :: ColumnType :: Uuid))",
+            ),
+        ];
+
+        for (typ, expected) in cases {
+            assert_eq!(typ.to_token_stream().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_from_str_round_trip() {
+        let types = [
+            ColumnType::Array(Box::new(ColumnType::Array(Box::new(ColumnType::Integer)))),
+            ColumnType::Bit(Some(3)),
+            ColumnType::BitVarying(None),
+            ColumnType::Timestamp(Some(6)),
+            ColumnType::TimestampTz,
+            ColumnType::Domain("email".to_string()),
+            ColumnType::Enum("status".to_string()),
+            ColumnType::Numeric(Some(vec![12, 2])),
+            ColumnType::DoublePrecision,
+            ColumnType::Float(24),
+            ColumnType::Int,
+            ColumnType::TsTzMultiRange,
+            ColumnType::VarChar(Some(255)),
+            ColumnType::Char(None),
+            ColumnType::Jsonb,
+        ];
+
+        for typ in types {
+            let parsed: ColumnType = typ.to_string().parse().unwrap();
+            assert_eq!(parsed.to_string(), typ.to_string());
+        }
+    }
+
+    #[test]
+    fn test_from_str_aliases() {
+        let cases = [
+            ("int4", "integer"),
+            ("INT8", "bigint"),
+            ("bool", "boolean"),
+            ("character varying(64)", "varchar(64)"),
+            ("character  varying", "varchar"),
+            ("character(2)", "char(2)"),
+            ("double precision", "double_precision"),
+            ("float8", "double_precision"),
+            ("decimal(10, 2)", "numeric(10,2)"),
+            ("timestamp with time zone", "timestamptz"),
+            ("timestamp(3) without time zone", "timestamp(3)"),
+            ("time with time zone", "timetz"),
+            ("bit varying(8)", "bit_varying(8)"),
+            ("serial8", "bigserial"),
+            ("int4[]", "integer[]"),
+            ("text[3]", "text[]"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<ColumnType>().unwrap().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_from_str_keeps_name_case() {
+        let types = [
+            ColumnType::Enum("OrderStatus".to_string()),
+            ColumnType::Domain("EmailAddress".to_string()),
+            ColumnType::Array(Box::new(ColumnType::Enum("OrderStatus".to_string()))),
+        ];
+
+        for typ in types {
+            let parsed = typ.to_string().parse::<ColumnType>().unwrap();
+            assert_eq!(parsed.to_string(), typ.to_string());
+        }
+        assert_eq!(
+            "ENUM.OrderStatus".parse::<ColumnType>().unwrap().to_string(),
+            "enum.OrderStatus"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keeps_name_case() {
+        let typ = ColumnType::Enum("OrderStatus".to_string());
+        let json = serde_json::to_string(&typ).unwrap();
+
+        assert_eq!(json, "\"enum.OrderStatus\"");
+        assert_eq!(serde_json::from_str::<ColumnType>(&json).unwrap(), typ);
+    }
+
+    #[test]
+    fn test_from_str_errors() {
+        for input in [
+            "",
+            "integr",
+            "text(5)",
+            "varchar(a)",
+            "varchar(1, 2)",
+            "numeric(12",
+            "int]",
+        ] {
+            assert!(
+                input.parse::<ColumnType>().is_err(),
+                "{} should not parse",
+                input
+            );
+        }
     }
 
     #[test]
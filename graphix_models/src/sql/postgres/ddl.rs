@@ -0,0 +1,441 @@
+//! Plain Postgres DDL for environments without Atlas.
+
+use crate::descriptor::{
+    ColumnDefault, EdgeDescriptor, EntityDescriptor, EntityFieldDescriptor, EnumDescriptor,
+    IndexDescriptor, IndexPart,
+};
+use crate::diff::{Change, SchemaDiff};
+use crate::schema::{schema_label, Schema};
+use crate::sql::postgres::ColumnType;
+use crate::sql::LogicalType;
+
+/// Quotes an identifier, doubling any `"` in it.
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quotes a string literal, doubling any `'` in it.
+pub fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// A schema qualified name such as `"public"."users"`, `schema` is a reference like
+/// `schema.public`.
+pub fn qualified(schema: &str, name: &str) -> String {
+    format!(
+        "{}.{}",
+        quote_ident(schema_label(schema)),
+        quote_ident(name)
+    )
+}
+
+/// The SQL spelling of a column type. Enums are looked up in `enums` for their schema, anything
+/// else user defined is assumed to live in `schema`.
+pub fn column_type_sql(typ: &ColumnType, schema: &str, enums: &[EnumDescriptor]) -> String {
+    match typ {
+        ColumnType::Array(of) => format!("{}[]", column_type_sql(of, schema, enums)),
+        ColumnType::Enum(name) => {
+            let schema = enums
+                .iter()
+                .find(|e| e.name == *name)
+                .map_or(schema, |e| e.schema_name.as_str());
+            qualified(schema, name)
+        }
+        ColumnType::Domain(name) => qualified(schema, name),
+        ColumnType::BitVarying(Some(size)) => format!("bit varying({})", size),
+        ColumnType::BitVarying(None) => "bit varying".to_string(),
+        ColumnType::DoublePrecision => "double precision".to_string(),
+        other => other.to_string(),
+    }
+}
+
+pub fn default_sql(default: &ColumnDefault) -> String {
+    match default {
+        ColumnDefault::Bool(b) => b.to_string(),
+        ColumnDefault::Int(i) => i.to_string(),
+        ColumnDefault::Float(f) => f.clone(),
+        ColumnDefault::Str(s) => quote_literal(s),
+        ColumnDefault::Expr(e) => e.clone(),
+    }
+}
+
+pub fn create_schema(name: &str) -> String {
+    format!("CREATE SCHEMA IF NOT EXISTS {};", quote_ident(name))
+}
+
+pub fn create_enum(desc: &EnumDescriptor) -> String {
+    format!(
+        "CREATE TYPE {} AS ENUM ({});",
+        qualified(&desc.schema_name, &desc.name),
+        desc.values
+            .iter()
+            .map(|v| quote_literal(v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// A column definition as used by `CREATE TABLE` and `ADD COLUMN`.
+pub fn column_sql(table: &EntityDescriptor, field: &EntityFieldDescriptor) -> String {
+    let mut sql = format!(
+        "{} {}",
+        quote_ident(&field.column_name),
+        column_type_sql(&(&field.sql_type).into(), &table.schema_name, &table.enums)
+    );
+
+    if !field.nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &field.default {
+        sql.push_str(" DEFAULT ");
+        sql.push_str(&default_sql(default));
+    }
+
+    sql
+}
+
+/// `CREATE TABLE` with the columns and primary key, indexes and foreign keys are separate
+/// statements.
+pub fn create_table(desc: &EntityDescriptor) -> String {
+    let mut lines: Vec<String> = desc.fields.iter().map(|f| column_sql(desc, f)).collect();
+
+    if !desc.primary_key.is_empty() {
+        lines.push(format!("PRIMARY KEY ({})", column_list(&desc.primary_key)));
+    }
+
+    format!(
+        "CREATE TABLE {} (\n  {}\n);",
+        qualified(&desc.schema_name, &desc.table_name),
+        lines.join(",\n  ")
+    )
+}
+
+pub fn create_index(table: &EntityDescriptor, index: &IndexDescriptor) -> String {
+    let mut sql = format!(
+        "CREATE {}INDEX {} ON {}",
+        if index.unique { "UNIQUE " } else { "" },
+        quote_ident(&index.name),
+        qualified(&table.schema_name, &table.table_name)
+    );
+
+    if let Some(method) = index.method {
+        sql.push_str(&format!(" USING {}", method));
+    }
+
+    let parts = index
+        .parts
+        .iter()
+        .map(|part| match part {
+            IndexPart::Column(col) => quote_ident(col),
+            IndexPart::Expr(expr) => format!("({})", expr),
+        })
+        .collect::<Vec<_>>();
+    sql.push_str(&format!(" ({})", parts.join(", ")));
+
+    if let Some(predicate) = &index.predicate {
+        sql.push_str(&format!(" WHERE {}", predicate));
+    }
+
+    sql.push(';');
+    sql
+}
+
+/// The foreign key constraint of a non-inverse edge.
+pub fn add_foreign_key(table: &EntityDescriptor, edge: &EdgeDescriptor) -> String {
+    let mut sql = format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+        qualified(&table.schema_name, &table.table_name),
+        quote_ident(&edge.foreign_key_name(&table.table_name)),
+        column_list(&edge.columns),
+        qualified(&edge.target_schema, &edge.target_table),
+        column_list(&edge.ref_columns)
+    );
+
+    if let Some(action) = edge.on_update {
+        sql.push_str(&format!(" ON UPDATE {}", action));
+    }
+    if let Some(action) = edge.on_delete {
+        sql.push_str(&format!(" ON DELETE {}", action));
+    }
+
+    sql.push(';');
+    sql
+}
+
+/// Every statement needed to create `schema` from scratch: schemas, enums, tables, indexes and
+/// finally foreign keys. Tables are ordered so that the tables a foreign key references come
+/// before the table holding it, tables in a reference cycle fall back to name order.
+pub fn schema_statements(schema: &Schema) -> Vec<String> {
+    let tables = schema.tables_by_dependency();
+
+    let mut statements: Vec<String> = schema.schemas().into_iter().map(create_schema).collect();
+    statements.extend(schema.enums().map(create_enum));
+    statements.extend(tables.iter().map(|t| create_table(t)));
+    for table in &tables {
+        statements.extend(table.indexes.iter().map(|i| create_index(table, i)));
+    }
+    for table in &tables {
+        statements.extend(table.foreign_keys().map(|e| add_foreign_key(table, e)));
+    }
+
+    statements
+}
+
+/// The statements creating a single entity along with its join tables and enums.
+pub fn entity_statements(desc: &EntityDescriptor) -> Vec<String> {
+    let mut schema = Schema::new();
+    schema.add(desc);
+    schema_statements(&schema)
+}
+
+/// The statement applying a single change.
+pub fn change_sql(change: &Change) -> String {
+    let table_name = |t: &EntityDescriptor| qualified(&t.schema_name, &t.table_name);
+
+    match change {
+        Change::AddSchema(name) => create_schema(name),
+        Change::AddEnum(desc) => create_enum(desc),
+        Change::RenameEnum { desc, name } => format!(
+            "ALTER TYPE {} RENAME TO {};",
+            qualified(&desc.schema_name, &desc.name),
+            quote_ident(name)
+        ),
+        Change::AddEnumValue { desc, value } => format!(
+            "ALTER TYPE {} ADD VALUE {};",
+            qualified(&desc.schema_name, &desc.name),
+            quote_literal(value)
+        ),
+        Change::DropForeignKey { table, edge } => format!(
+            "ALTER TABLE {} DROP CONSTRAINT {};",
+            table_name(table),
+            quote_ident(&edge.foreign_key_name(&table.table_name))
+        ),
+        Change::DropIndex { table, index } => {
+            format!("DROP INDEX {};", qualified(&table.schema_name, &index.name))
+        }
+        Change::DropTable(table) => format!("DROP TABLE {};", table_name(table)),
+        Change::AddTable(table) => create_table(table),
+        Change::AddColumn { table, field } => format!(
+            "ALTER TABLE {} ADD COLUMN {};",
+            table_name(table),
+            column_sql(table, field)
+        ),
+        Change::AlterColumnType {
+            table,
+            column,
+            from,
+            to,
+        } => {
+            let typ = column_type_sql(&to.into(), &table.schema_name, &table.enums);
+            // there's no cast from one enum to another, going through text covers both enums and
+            // arrays of them
+            let using = if to.enum_name().is_some() {
+                format!(" USING {}::text::{}", quote_ident(column), typ)
+            } else if !casts_automatically(from, to) {
+                format!(" USING {}::{}", quote_ident(column), typ)
+            } else {
+                String::new()
+            };
+
+            format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {}{};",
+                table_name(table),
+                quote_ident(column),
+                typ,
+                using
+            )
+        }
+        Change::AlterColumnNullable {
+            table,
+            column,
+            nullable,
+        } => format!(
+            "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL;",
+            table_name(table),
+            quote_ident(column),
+            if *nullable { "DROP" } else { "SET" }
+        ),
+        Change::AlterColumnDefault {
+            table,
+            column,
+            default,
+        } => format!(
+            "ALTER TABLE {} ALTER COLUMN {} {};",
+            table_name(table),
+            quote_ident(column),
+            match default {
+                Some(default) => format!("SET DEFAULT {}", default_sql(default)),
+                None => "DROP DEFAULT".to_string(),
+            }
+        ),
+        Change::AlterPrimaryKey { table, from, to } => {
+            // postgres names the constraint `{table}_pkey` unless told otherwise
+            let mut actions = Vec::new();
+            if !from.is_empty() {
+                actions.push(format!(
+                    "DROP CONSTRAINT {}",
+                    quote_ident(&format!("{}_pkey", table.table_name))
+                ));
+            }
+            if !to.is_empty() {
+                actions.push(format!("ADD PRIMARY KEY ({})", column_list(to)));
+            }
+            format!("ALTER TABLE {} {};", table_name(table), actions.join(", "))
+        }
+        Change::DropColumn { table, column } => format!(
+            "ALTER TABLE {} DROP COLUMN {};",
+            table_name(table),
+            quote_ident(column)
+        ),
+        Change::AddIndex { table, index } => create_index(table, index),
+        Change::AddForeignKey { table, edge } => add_foreign_key(table, edge),
+        Change::DropEnum(desc) => {
+            format!("DROP TYPE {};", qualified(&desc.schema_name, &desc.name))
+        }
+        Change::DropSchema(name) => format!("DROP SCHEMA {};", quote_ident(name)),
+    }
+}
+
+/// The statements migrating a database from one schema to the other, in the diff's order.
+pub fn diff_statements(diff: &SchemaDiff) -> Vec<String> {
+    diff.changes.iter().map(change_sql).collect()
+}
+
+/// Whether postgres converts a `from` column to `to` without a `USING` clause, which it does
+/// for implicit and assignment casts: between numbers, between dates and timestamps, from
+/// anything to text, and between arrays of those.
+fn casts_automatically(from: &LogicalType, to: &LogicalType) -> bool {
+    use LogicalType as L;
+
+    let number = |t: &LogicalType| {
+        matches!(
+            t,
+            L::SmallInt
+                | L::Int
+                | L::BigInt
+                | L::SmallSerial
+                | L::Serial
+                | L::BigSerial
+                | L::Real
+                | L::Double
+                | L::Decimal { .. }
+        )
+    };
+    let temporal = |t: &LogicalType| matches!(t, L::Date | L::Timestamp(_) | L::TimestampTz);
+
+    match (from, to) {
+        (from, to) if from == to => true,
+        (L::Array(from), L::Array(to)) => casts_automatically(from, to),
+        (L::Array(_), _) | (_, L::Array(_)) => false,
+        (_, L::Text | L::VarChar(_) | L::Char(_)) => true,
+        (from, to) => (number(from) && number(to)) || (temporal(from) && temporal(to)),
+    }
+}
+
+fn column_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote_ident("users"), "\"users\"");
+        assert_eq!(quote_ident("we\"ird"), "\"we\"\"ird\"");
+        assert_eq!(quote_literal("it's"), "'it''s'");
+        assert_eq!(qualified("schema.public", "users"), "\"public\".\"users\"");
+    }
+
+    #[test]
+    fn test_column_type_sql() {
+        let enums = vec![EnumDescriptor {
+            name: "status".to_string(),
+            schema_name: "schema.types".to_string(),
+            values: vec![],
+        }];
+
+        assert_eq!(
+            column_type_sql(&ColumnType::DoublePrecision, "schema.public", &enums),
+            "double precision"
+        );
+        assert_eq!(
+            column_type_sql(&ColumnType::BitVarying(Some(8)), "schema.public", &enums),
+            "bit varying(8)"
+        );
+        assert_eq!(
+            column_type_sql(
+                &ColumnType::Numeric(Some(vec![12, 2])),
+                "schema.public",
+                &enums
+            ),
+            "numeric(12,2)"
+        );
+        assert_eq!(
+            column_type_sql(
+                &ColumnType::Array(Box::new(ColumnType::Enum("status".to_string()))),
+                "schema.public",
+                &enums
+            ),
+            "\"types\".\"status\"[]"
+        );
+        assert_eq!(
+            column_type_sql(
+                &ColumnType::Domain("email".to_string()),
+                "schema.public",
+                &enums
+            ),
+            "\"public\".\"email\""
+        );
+    }
+
+    #[test]
+    fn test_casts_automatically() {
+        use LogicalType as L;
+
+        assert!(casts_automatically(&L::Int, &L::BigInt));
+        assert!(casts_automatically(
+            &L::BigInt,
+            &L::Decimal {
+                precision: Some(12),
+                scale: None
+            }
+        ));
+        assert!(casts_automatically(&L::VarChar(Some(64)), &L::Text));
+        assert!(casts_automatically(&L::Uuid, &L::Text));
+        assert!(casts_automatically(&L::Timestamp(None), &L::TimestampTz));
+        assert!(casts_automatically(
+            &L::Array(Box::new(L::SmallInt)),
+            &L::Array(Box::new(L::Int))
+        ));
+
+        assert!(!casts_automatically(&L::Text, &L::Int));
+        assert!(!casts_automatically(&L::Text, &L::Uuid));
+        assert!(!casts_automatically(&L::Text, &L::Json));
+        assert!(!casts_automatically(&L::Bool, &L::Int));
+        assert!(!casts_automatically(&L::Int, &L::Array(Box::new(L::Int))));
+    }
+
+    #[test]
+    fn test_default_sql() {
+        assert_eq!(default_sql(&ColumnDefault::Bool(true)), "true");
+        assert_eq!(default_sql(&ColumnDefault::Int(-1)), "-1");
+        assert_eq!(
+            default_sql(&ColumnDefault::Float("1.50".to_string())),
+            "1.50"
+        );
+        assert_eq!(
+            default_sql(&ColumnDefault::Str("o'k".to_string())),
+            "'o''k'"
+        );
+        assert_eq!(
+            default_sql(&ColumnDefault::Expr("now()".to_string())),
+            "now()"
+        );
+    }
+}
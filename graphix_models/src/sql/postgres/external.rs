@@ -0,0 +1,48 @@
+//! `SqlType` implementations for types from std and, behind their cargo features, from other
+//! crates.
+
+use super::{literal, AcceptsDefault, ColumnType, SqlType};
+
+impl_sql_type! {
+    std::net::IpAddr [Str] => ColumnType::INet,
+    std::net::Ipv4Addr [Str] => ColumnType::INet,
+    std::net::Ipv6Addr [Str] => ColumnType::INet,
+}
+
+#[cfg(feature = "uuid")]
+impl_sql_type! {
+    uuid::Uuid [Str] => ColumnType::Uuid,
+}
+
+#[cfg(feature = "time")]
+impl_sql_type! {
+    time::OffsetDateTime [Str] => ColumnType::TimestampTz,
+    time::PrimitiveDateTime [Str] => ColumnType::Timestamp(None),
+    time::Date [Str] => ColumnType::Date,
+    time::Time [Str] => ColumnType::Time,
+}
+
+#[cfg(feature = "chrono")]
+impl_sql_type! {
+    chrono::DateTime<chrono::Utc> [Str] => ColumnType::TimestampTz,
+    chrono::NaiveDateTime [Str] => ColumnType::Timestamp(None),
+    chrono::NaiveDate [Str] => ColumnType::Date,
+    chrono::NaiveTime [Str] => ColumnType::Time,
+}
+
+#[cfg(feature = "serde_json")]
+impl_sql_type! {
+    serde_json::Value [Str] => ColumnType::Jsonb,
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_sql_type! {
+    rust_decimal::Decimal [Int, Float] => ColumnType::Numeric(None),
+}
+
+#[cfg(feature = "ipnetwork")]
+impl_sql_type! {
+    ipnetwork::IpNetwork [Str] => ColumnType::Cidr,
+    ipnetwork::Ipv4Network [Str] => ColumnType::Cidr,
+    ipnetwork::Ipv6Network [Str] => ColumnType::Cidr,
+}
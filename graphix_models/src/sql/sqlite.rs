@@ -0,0 +1,131 @@
+//! SQLite support, mainly so schemas and queries can be tested in-process without a Postgres
+//! server. Logical types are mapped onto the few types SQLite has.
+
+use crate::descriptor::{EntityDescriptor, EntityFieldDescriptor};
+use crate::schema::Schema;
+use crate::sql::{postgres, Dialect, LogicalType};
+use hcl::{Block, Body, Expression, Variable};
+use std::fmt::Formatter;
+
+pub mod atlas;
+pub mod ddl;
+
+/// The type affinities SQLite columns are declared with. SQLite has no schemas, enums, arrays or
+/// length limits, so everything without a closer match is stored as `TEXT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    Integer,
+    Real,
+    Numeric,
+    Text,
+    Blob,
+}
+impl ColumnType {
+    /// The SQLite type of a column. Booleans are stored as `0`/`1`, timestamps, uuids and json
+    /// as text.
+    pub fn from_logical(typ: &LogicalType) -> Self {
+        use postgres::ColumnType as Pg;
+
+        match typ {
+            LogicalType::Bool
+            | LogicalType::SmallInt
+            | LogicalType::Int
+            | LogicalType::BigInt
+            | LogicalType::SmallSerial
+            | LogicalType::Serial
+            | LogicalType::BigSerial
+            | LogicalType::Postgres(Pg::Int) => Self::Integer,
+            LogicalType::Real | LogicalType::Double | LogicalType::Postgres(Pg::Float(_)) => {
+                Self::Real
+            }
+            LogicalType::Decimal { .. } | LogicalType::Postgres(Pg::Numeric(_) | Pg::Money) => {
+                Self::Numeric
+            }
+            LogicalType::Bytes => Self::Blob,
+            _ => Self::Text,
+        }
+    }
+}
+impl ColumnType {
+    /// The expression Atlas HCL uses for this type, e.g. `integer`.
+    pub fn as_atlas_expr(&self) -> Expression {
+        Variable::new(self.to_string().to_lowercase())
+            .expect("type names are valid identifiers")
+            .into()
+    }
+}
+impl std::fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer => write!(f, "INTEGER"),
+            Self::Real => write!(f, "REAL"),
+            Self::Numeric => write!(f, "NUMERIC"),
+            Self::Text => write!(f, "TEXT"),
+            Self::Blob => write!(f, "BLOB"),
+        }
+    }
+}
+
+/// SQLite, for tests without a database server. Every logical type has a column type to fall
+/// back to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sqlite;
+impl Dialect for Sqlite {
+    type ColumnType = ColumnType;
+    type Error = std::convert::Infallible;
+
+    fn column_type(
+        &self,
+        _table: &EntityDescriptor,
+        field: &EntityFieldDescriptor,
+    ) -> Result<ColumnType, Self::Error> {
+        Ok(ColumnType::from_logical(&field.sql_type))
+    }
+
+    fn quote_ident(&self, ident: &str) -> String {
+        ddl::quote_ident(ident)
+    }
+
+    fn table_hcl(&self, table: &EntityDescriptor) -> Result<Block, Self::Error> {
+        Ok(atlas::table_hcl(table))
+    }
+
+    fn schema_hcl(&self, schema: &Schema) -> Result<Body, Self::Error> {
+        Ok(atlas::schema_hcl(schema))
+    }
+
+    fn schema_statements(&self, schema: &Schema) -> Result<Vec<String>, Self::Error> {
+        Ok(ddl::schema_statements(schema))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use postgres::ColumnType as Pg;
+
+    #[test]
+    fn test_from_logical() {
+        let cases = [
+            (Pg::Boolean, ColumnType::Integer),
+            (Pg::BigSerial, ColumnType::Integer),
+            (Pg::DoublePrecision, ColumnType::Real),
+            (Pg::Numeric(Some(vec![10, 2])), ColumnType::Numeric),
+            (Pg::Money, ColumnType::Numeric),
+            (Pg::ByteA, ColumnType::Blob),
+            (Pg::Uuid, ColumnType::Text),
+            (Pg::TimestampTz, ColumnType::Text),
+            (Pg::Enum("status".to_string()), ColumnType::Text),
+            (Pg::Array(Box::new(Pg::Integer)), ColumnType::Text),
+        ];
+
+        for (pg, sqlite) in cases {
+            assert_eq!(
+                ColumnType::from_logical(&pg.clone().into()),
+                sqlite,
+                "{}",
+                pg
+            );
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Atlas HCL for SQLite. SQLite has a single schema, `main`, which every table is placed in.
+
+use crate::descriptor::{ColumnDefault, EntityDescriptor, EntityFieldDescriptor, IndexDescriptor};
+use crate::entity::{default_atlas_expr, foreign_key_atlas_hcl, index_atlas_hcl, traversal};
+use crate::schema::Schema;
+use crate::sql::sqlite::{ddl, ColumnType};
+use hcl::expr::FuncCall;
+use hcl::{Block, Body, Expression, Number};
+
+pub fn column_hcl(field: &EntityFieldDescriptor) -> Block {
+    let mut column = Block::builder("column")
+        .add_label(field.column_name.clone())
+        .add_attribute((
+            "type",
+            ColumnType::from_logical(&field.sql_type).as_atlas_expr(),
+        ))
+        .add_attribute(("null", field.nullable));
+
+    if let Some(default) = &field.default {
+        column = column.add_attribute(("default", default_hcl(default)));
+    }
+
+    column.build()
+}
+
+/// A `table` block. Enum columns get a `check` on their values, index methods are left out.
+pub fn table_hcl(desc: &EntityDescriptor) -> Block {
+    let mut builder = Block::builder("table")
+        .add_label(&desc.table_name)
+        .add_attribute(("schema", traversal("schema.main")));
+
+    for field in &desc.fields {
+        builder = builder.add_block(column_hcl(field));
+    }
+
+    if !desc.primary_key.is_empty() {
+        builder = builder.add_block(
+            Block::builder("primary_key")
+                .add_attribute((
+                    "columns",
+                    desc.primary_key
+                        .iter()
+                        .map(|col| traversal(&format!("column.{}", col)))
+                        .collect::<Vec<_>>(),
+                ))
+                .build(),
+        );
+    }
+
+    for edge in desc.foreign_keys() {
+        // every table is in `main`, so references are never qualified
+        builder = builder.add_block(foreign_key_atlas_hcl(
+            &desc.table_name,
+            &edge.target_schema,
+            edge,
+        ));
+    }
+
+    for index in &desc.indexes {
+        builder = builder.add_block(index_atlas_hcl(&IndexDescriptor {
+            method: None,
+            ..index.clone()
+        }));
+    }
+
+    for field in &desc.fields {
+        if let Some(check) = ddl::enum_check(desc, field) {
+            builder = builder.add_block(
+                Block::builder("check")
+                    .add_label(format!("{}_{}_check", desc.table_name, field.column_name))
+                    .add_attribute(("expr", check))
+                    .build(),
+            );
+        }
+    }
+
+    builder.build()
+}
+
+/// The `main` schema and every table of `schema`, join tables included.
+pub fn schema_hcl(schema: &Schema) -> Body {
+    Body::builder()
+        .add_block(Block::builder("schema").add_label("main").build())
+        .add_blocks(schema.tables().map(table_hcl))
+        .build()
+}
+
+/// Booleans are `0`/`1`, expressions as in [`ddl::default_sql`].
+fn default_hcl(default: &ColumnDefault) -> Expression {
+    match default {
+        ColumnDefault::Bool(b) => Expression::Number(Number::from(*b as i64)),
+        ColumnDefault::Expr(_) => FuncCall::builder("sql")
+            .arg(ddl::default_sql(default))
+            .build()
+            .into(),
+        _ => default_atlas_expr(default),
+    }
+}
@@ -0,0 +1,188 @@
+//! SQLite DDL. SQLite can't add constraints to existing tables, so foreign keys are part of
+//! `CREATE TABLE`, and it has a single namespace, so tables are never schema qualified.
+
+use crate::descriptor::{
+    ColumnDefault, EdgeDescriptor, EntityDescriptor, EntityFieldDescriptor, IndexDescriptor,
+    IndexPart,
+};
+use crate::schema::Schema;
+use crate::sql::sqlite::ColumnType;
+use crate::sql::LogicalType;
+
+// identifiers and literals are quoted the same way as in postgres
+pub use crate::sql::postgres::ddl::{quote_ident, quote_literal};
+
+pub fn column_type_sql(typ: &LogicalType) -> String {
+    ColumnType::from_logical(typ).to_string()
+}
+
+/// Literals as SQLite reads them, expressions in the parentheses SQLite requires. `now()` has no
+/// SQLite equivalent and becomes `CURRENT_TIMESTAMP`.
+pub fn default_sql(default: &ColumnDefault) -> String {
+    match default {
+        ColumnDefault::Bool(b) => (*b as i32).to_string(),
+        ColumnDefault::Int(i) => i.to_string(),
+        ColumnDefault::Float(f) => f.clone(),
+        ColumnDefault::Str(s) => quote_literal(s),
+        ColumnDefault::Expr(e) => match e.to_lowercase().as_str() {
+            "now()" | "current_timestamp" => "CURRENT_TIMESTAMP".to_string(),
+            _ => format!("({})", e),
+        },
+    }
+}
+
+/// A column definition. Enum columns get a `CHECK` restricting them to the enum's values.
+pub fn column_sql(table: &EntityDescriptor, field: &EntityFieldDescriptor) -> String {
+    let mut sql = format!(
+        "{} {}",
+        quote_ident(&field.column_name),
+        column_type_sql(&field.sql_type)
+    );
+
+    if !field.nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &field.default {
+        sql.push_str(" DEFAULT ");
+        sql.push_str(&default_sql(default));
+    }
+    if let Some(check) = enum_check(table, field) {
+        sql.push_str(&format!(" CHECK ({})", check));
+    }
+
+    sql
+}
+
+/// The condition restricting an enum column to the enum's values.
+pub fn enum_check(table: &EntityDescriptor, field: &EntityFieldDescriptor) -> Option<String> {
+    let LogicalType::Enum(name) = &field.sql_type else {
+        return None;
+    };
+    let e = table.enums.iter().find(|e| e.name == *name)?;
+
+    let values = e
+        .values
+        .iter()
+        .map(|v| quote_literal(v))
+        .collect::<Vec<_>>();
+    Some(format!(
+        "{} IN ({})",
+        quote_ident(&field.column_name),
+        values.join(", ")
+    ))
+}
+
+/// The foreign key clause of a non-inverse edge.
+pub fn foreign_key_sql(edge: &EdgeDescriptor) -> String {
+    let mut sql = format!(
+        "FOREIGN KEY ({}) REFERENCES {} ({})",
+        column_list(&edge.columns),
+        quote_ident(&edge.target_table),
+        column_list(&edge.ref_columns)
+    );
+
+    if let Some(action) = edge.on_update {
+        sql.push_str(&format!(" ON UPDATE {}", action));
+    }
+    if let Some(action) = edge.on_delete {
+        sql.push_str(&format!(" ON DELETE {}", action));
+    }
+
+    sql
+}
+
+/// `CREATE TABLE` with the columns, primary key and foreign keys, indexes are separate
+/// statements.
+pub fn create_table(desc: &EntityDescriptor) -> String {
+    let mut lines: Vec<String> = desc.fields.iter().map(|f| column_sql(desc, f)).collect();
+
+    if !desc.primary_key.is_empty() {
+        lines.push(format!("PRIMARY KEY ({})", column_list(&desc.primary_key)));
+    }
+    lines.extend(desc.foreign_keys().map(foreign_key_sql));
+
+    format!(
+        "CREATE TABLE {} (\n  {}\n);",
+        quote_ident(&desc.table_name),
+        lines.join(",\n  ")
+    )
+}
+
+/// `CREATE INDEX`, SQLite only has btree indexes so the method is left out.
+pub fn create_index(table: &EntityDescriptor, index: &IndexDescriptor) -> String {
+    let parts = index
+        .parts
+        .iter()
+        .map(|part| match part {
+            IndexPart::Column(col) => quote_ident(col),
+            IndexPart::Expr(expr) => format!("({})", expr),
+        })
+        .collect::<Vec<_>>();
+
+    let mut sql = format!(
+        "CREATE {}INDEX {} ON {} ({})",
+        if index.unique { "UNIQUE " } else { "" },
+        quote_ident(&index.name),
+        quote_ident(&table.table_name),
+        parts.join(", ")
+    );
+
+    if let Some(predicate) = &index.predicate {
+        sql.push_str(&format!(" WHERE {}", predicate));
+    }
+
+    sql.push(';');
+    sql
+}
+
+/// Every statement needed to create `schema` from scratch, tables first in dependency order
+/// followed by their indexes. SQLite only enforces the foreign keys with
+/// `PRAGMA foreign_keys = ON`.
+pub fn schema_statements(schema: &Schema) -> Vec<String> {
+    let tables = schema.tables_by_dependency();
+
+    let mut statements: Vec<String> = tables.iter().map(|t| create_table(t)).collect();
+    for table in &tables {
+        statements.extend(table.indexes.iter().map(|i| create_index(table, i)));
+    }
+
+    statements
+}
+
+/// The statements creating a single entity along with its join tables.
+pub fn entity_statements(desc: &EntityDescriptor) -> Vec<String> {
+    let mut schema = Schema::new();
+    schema.add(desc);
+    schema_statements(&schema)
+}
+
+fn column_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_sql() {
+        assert_eq!(default_sql(&ColumnDefault::Bool(true)), "1");
+        assert_eq!(default_sql(&ColumnDefault::Int(-1)), "-1");
+        assert_eq!(
+            default_sql(&ColumnDefault::Str("o'k".to_string())),
+            "'o''k'"
+        );
+        assert_eq!(
+            default_sql(&ColumnDefault::Expr("now()".to_string())),
+            "CURRENT_TIMESTAMP"
+        );
+        assert_eq!(
+            default_sql(&ColumnDefault::Expr("random()".to_string())),
+            "(random())"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use graphix::atlas::AtlasError;
+use graphix::codegen::rust_source;
+use graphix::descriptor::EdgeCardinality;
+use graphix::diff::SchemaDiff;
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::{EdgeSchema, Entity, SqlEnum};
+
+// the entities generated from `legacy.hcl`, compiled to check the generated source is valid
+#[path = "snapshots/legacy.rs"]
+#[allow(dead_code)]
+mod legacy;
+
+const LEGACY_HCL: &str = "tests/snapshots/legacy.hcl";
+const LEGACY_RS: &str = "tests/snapshots/legacy.rs";
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "teams", index(parts = [expr("lower(name)")], name = "idx_teams_name"))]
+#[allow(dead_code)]
+struct Team {
+    #[graphix(primary_key)]
+    id: i32,
+    name: String,
+}
+
+#[derive(EdgeSchema)]
+#[allow(dead_code)]
+struct Seat {
+    role: Role,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "members",
+    edge(to = Team, field = "team_id", on_delete = "set_null"),
+    edge(to = Team, m2m, through = "seats", edge_schema = Seat),
+    index(columns = ["team_id", "nickname"], unique)
+)]
+#[allow(dead_code)]
+struct Member {
+    #[graphix(primary_key)]
+    id: uuid::Uuid,
+    #[graphix(unique, sql_type = "varchar(64)")]
+    nickname: String,
+    team_id: Option<i32>,
+    #[graphix(default = 1.5)]
+    score: f64,
+}
+
+fn legacy() -> Schema {
+    Schema::from_atlas_hcl(&std::fs::read_to_string(LEGACY_HCL).unwrap()).unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    let schema: Schema = [Team::descriptor(), Member::descriptor()]
+        .into_iter()
+        .collect();
+    let hcl = hcl::to_string(&schema.as_atlas_hcl()).unwrap();
+    let parsed = Schema::from_atlas_hcl(&hcl).unwrap();
+
+    assert!(SchemaDiff::between(&schema, &parsed).is_empty());
+    assert_eq!(
+        parsed.enums().collect::<Vec<_>>(),
+        schema.enums().collect::<Vec<_>>()
+    );
+
+    let members = parsed.table("schema.public", "members").unwrap();
+    assert_eq!(members.name, "Member");
+    assert!(members
+        .fields
+        .iter()
+        .any(|f| f.column_name == "nickname" && f.unique));
+    assert_eq!(members.edges[0].target, "Team");
+
+    // the join table is read back as a table of its own
+    let seats = parsed.table("schema.public", "seats").unwrap();
+    assert_eq!(seats.primary_key, ["member_id", "team_id"]);
+    assert_eq!(seats.enums[0].name, "role");
+}
+
+#[test]
+fn test_legacy_schema() {
+    let schema = legacy();
+
+    let orders = schema.table("schema.billing", "orders").unwrap();
+    assert_eq!(orders.edges[0].target_schema, "schema.public");
+    assert_eq!(orders.edges[0].target_table, "customers");
+
+    let shipments = schema.table("schema.billing", "shipments").unwrap();
+    assert_eq!(shipments.edges[0].cardinality, EdgeCardinality::O2O);
+
+    let customers = schema.table("schema.public", "customers").unwrap();
+    assert_eq!(customers.fields[2].name, "type_");
+    assert_eq!(customers.fields[2].typ, "Option<String>");
+
+    // views and other blocks graphix doesn't model are skipped
+    assert!(schema
+        .table("schema.public", "legacy_view_source")
+        .is_some());
+    assert_eq!(schema.tables().count(), 5);
+}
+
+/// Fails when the generated source changes, rerun with `GRAPHIX_UPDATE_SNAPSHOTS=1` to accept it.
+#[test]
+fn test_generated_source() {
+    let source = rust_source(&legacy());
+
+    if std::env::var("GRAPHIX_UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+        std::fs::write(LEGACY_RS, &source).unwrap();
+    }
+    assert_eq!(source, std::fs::read_to_string(LEGACY_RS).unwrap());
+}
+
+#[test]
+fn test_generated_entities() {
+    let generated: Schema = [
+        legacy::Customer::descriptor(),
+        legacy::Order::descriptor(),
+        legacy::Shipment::descriptor(),
+        legacy::AuditLog::descriptor(),
+        legacy::LegacyViewSource::descriptor(),
+    ]
+    .into_iter()
+    .collect();
+
+    let diff = SchemaDiff::between(&legacy(), &generated);
+    assert!(diff.is_empty(), "{:?}", diff.changes);
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        Schema::from_atlas_hcl("table \"t\" {"),
+        Err(AtlasError::Parse(_))
+    ));
+
+    let err = Schema::from_atlas_hcl(
+        r#"table "t" {
+             column "id" {
+               type = geometry
+             }
+           }"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with("`table.t.column.id`: "));
+
+    let err = Schema::from_atlas_hcl(
+        r#"table "t" {
+             column "a" {
+               type = integer
+             }
+             foreign_key "t_a_fkey" {
+               columns = [column.a]
+             }
+           }"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`table.t.foreign_key.t_a_fkey`: missing `ref_columns`"
+    );
+}
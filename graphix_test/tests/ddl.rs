@@ -0,0 +1,121 @@
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::sql::postgres::ddl;
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "posts", edge(to = User, field = "author_id", on_delete = "cascade"))]
+#[allow(dead_code)]
+struct Post {
+    #[graphix(primary_key)]
+    id: i64,
+    author_id: i64,
+    #[graphix(default = "untitled")]
+    title: String,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "users",
+    edge(to = Group, field = "group_id", on_delete = "set_null"),
+    index(columns = ["group_id"], predicate = "group_id IS NOT NULL", name = "idx_grouped_users")
+)]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    #[graphix(unique)]
+    username: String,
+    role: Role,
+    group_id: Option<i64>,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "groups")]
+#[allow(dead_code)]
+struct Group {
+    #[graphix(primary_key)]
+    id: i64,
+    #[graphix(default_expr = "now()")]
+    created_at: time::OffsetDateTime,
+}
+
+#[test]
+fn test_create_table() {
+    assert_eq!(
+        ddl::create_table(User::descriptor()),
+        "CREATE TABLE \"public\".\"users\" (\n  \
+         \"id\" bigint NOT NULL,\n  \
+         \"username\" text NOT NULL,\n  \
+         \"role\" \"public\".\"role\" NOT NULL,\n  \
+         \"group_id\" bigint,\n  \
+         PRIMARY KEY (\"id\")\n);"
+    );
+    assert!(ddl::create_table(Post::descriptor())
+        .contains("\"title\" text NOT NULL DEFAULT 'untitled'"));
+    assert!(ddl::create_table(Group::descriptor())
+        .contains("\"created_at\" timestamptz NOT NULL DEFAULT now()"));
+}
+
+#[test]
+fn test_indexes_and_foreign_keys() {
+    let desc = User::descriptor();
+
+    assert_eq!(
+        ddl::create_index(desc, &desc.indexes[0]),
+        "CREATE UNIQUE INDEX \"idx_users_username_unique\" ON \"public\".\"users\" (\"username\");"
+    );
+    assert_eq!(
+        ddl::create_index(desc, &desc.indexes[1]),
+        "CREATE INDEX \"idx_grouped_users\" ON \"public\".\"users\" (\"group_id\") WHERE group_id IS NOT NULL;"
+    );
+    assert_eq!(
+        ddl::add_foreign_key(desc, &desc.edges[0]),
+        "ALTER TABLE \"public\".\"users\" ADD CONSTRAINT \"users_group_id_fkey\" \
+         FOREIGN KEY (\"group_id\") REFERENCES \"public\".\"groups\" (\"id\") ON DELETE SET NULL;"
+    );
+}
+
+#[test]
+fn test_schema_statements() {
+    let statements = ddl::schema_statements(&Schema::registered());
+    let kinds: Vec<&str> = statements
+        .iter()
+        .map(|s| s.split(" (").next().unwrap())
+        .collect();
+
+    // referenced tables are created first even though `posts` sorts before `users`
+    assert_eq!(
+        kinds,
+        vec![
+            "CREATE SCHEMA IF NOT EXISTS \"public\";",
+            "CREATE TYPE \"public\".\"role\" AS ENUM",
+            "CREATE TABLE \"public\".\"groups\"",
+            "CREATE TABLE \"public\".\"users\"",
+            "CREATE TABLE \"public\".\"posts\"",
+            "CREATE UNIQUE INDEX \"idx_users_username_unique\" ON \"public\".\"users\"",
+            "CREATE INDEX \"idx_grouped_users\" ON \"public\".\"users\"",
+            "ALTER TABLE \"public\".\"users\" ADD CONSTRAINT \"users_group_id_fkey\" FOREIGN KEY",
+            "ALTER TABLE \"public\".\"posts\" ADD CONSTRAINT \"posts_author_id_fkey\" FOREIGN KEY",
+        ]
+    );
+    assert_eq!(
+        statements[1],
+        "CREATE TYPE \"public\".\"role\" AS ENUM ('admin', 'member');"
+    );
+}
+
+#[test]
+fn test_entity_statements() {
+    let statements = ddl::entity_statements(Post::descriptor());
+
+    assert_eq!(statements.len(), 3);
+    assert!(statements[1].starts_with("CREATE TABLE \"public\".\"posts\""));
+}
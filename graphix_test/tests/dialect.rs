@@ -0,0 +1,133 @@
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::sql::mysql::MySql;
+use graphix::sql::postgres::{ddl, Postgres};
+use graphix::sql::sqlite::Sqlite;
+use graphix::sql::{Dialect, LogicalType};
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Kind {
+    Note,
+    Task,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "items", index(columns = ["title"], name = "idx_items_title"))]
+#[allow(dead_code)]
+struct Item {
+    #[graphix(primary_key)]
+    id: i64,
+    title: String,
+    #[graphix(default = "note")]
+    kind: Kind,
+    #[graphix(default_expr = "now()")]
+    created_at: time::OffsetDateTime,
+    payload: Option<serde_json::Value>,
+}
+
+fn schema() -> Schema {
+    [Item::descriptor()].into_iter().collect()
+}
+
+/// The column types of `Item` in `dialect`.
+fn column_types<D: Dialect>(dialect: &D) -> Vec<String> {
+    let desc = Item::descriptor();
+    desc.fields
+        .iter()
+        .map(|f| dialect.column_type(desc, f).unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_logical_types() {
+    let types: Vec<&LogicalType> = Item::descriptor()
+        .fields
+        .iter()
+        .map(|f| &f.sql_type)
+        .collect();
+    assert_eq!(
+        types,
+        [
+            &LogicalType::BigInt,
+            &LogicalType::Text,
+            &LogicalType::Enum("kind".to_string()),
+            &LogicalType::TimestampTz,
+            &LogicalType::Json,
+        ]
+    );
+}
+
+#[test]
+fn test_column_types() {
+    assert_eq!(
+        column_types(&Postgres),
+        ["bigint", "text", "enum.kind", "timestamptz", "jsonb"]
+    );
+    assert_eq!(
+        column_types(&MySql::default()),
+        [
+            "bigint",
+            "varchar(255)",
+            "enum('note','task')",
+            "timestamp(6)",
+            "json"
+        ]
+    );
+    assert_eq!(
+        column_types(&Sqlite),
+        ["INTEGER", "TEXT", "TEXT", "TEXT", "TEXT"]
+    );
+}
+
+#[test]
+fn test_quote_ident() {
+    assert_eq!(Postgres.quote_ident("a\"b"), "\"a\"\"b\"");
+    assert_eq!(Sqlite.quote_ident("items"), "\"items\"");
+    assert_eq!(MySql::default().quote_ident("a`b"), "`a``b`");
+}
+
+#[test]
+fn test_postgres_output() {
+    // the postgres dialect is the output graphix had before dialects
+    let schema = schema();
+    assert_eq!(Postgres.schema_hcl(&schema).unwrap(), schema.as_atlas_hcl());
+    assert_eq!(
+        Postgres.schema_statements(&schema).unwrap(),
+        ddl::schema_statements(&schema)
+    );
+}
+
+#[test]
+fn test_statements() {
+    let schema = schema();
+
+    let create_table = |statements: Vec<String>| {
+        statements
+            .into_iter()
+            .find(|s| s.starts_with("CREATE TABLE"))
+            .unwrap()
+    };
+    assert!(create_table(Postgres.schema_statements(&schema).unwrap())
+        .contains("\"created_at\" timestamptz NOT NULL DEFAULT now()"));
+    assert!(
+        create_table(MySql::default().schema_statements(&schema).unwrap())
+            .contains("`created_at` timestamp(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)")
+    );
+    assert!(create_table(Sqlite.schema_statements(&schema).unwrap())
+        .contains("\"created_at\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP"));
+}
+
+#[test]
+fn test_sqlite_hcl() {
+    let hcl = hcl::to_string(&Sqlite.schema_hcl(&schema()).unwrap()).unwrap();
+
+    assert!(hcl.starts_with("schema \"main\" {}\n"));
+    assert!(hcl.contains(
+        "  column \"kind\" {\n    type = text\n    null = false\n    default = \"note\"\n  }"
+    ));
+    assert!(hcl.contains(
+        "  check \"items_kind_check\" {\n    expr = \"\\\"kind\\\" IN ('note', 'task')\"\n  }"
+    ));
+}
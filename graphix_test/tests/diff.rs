@@ -0,0 +1,207 @@
+use graphix::diff::{Change, SchemaDiff};
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::sql::postgres::ddl;
+use graphix::sql::LogicalType;
+
+mod v1 {
+    use graphix::{Entity, SqlEnum};
+
+    #[derive(SqlEnum)]
+    #[allow(dead_code)]
+    pub enum Status {
+        Active,
+        Banned,
+    }
+
+    #[derive(Entity)]
+    #[graphix(table_name = "groups")]
+    #[allow(dead_code)]
+    pub struct Group {
+        #[graphix(primary_key)]
+        id: i64,
+        name: String,
+    }
+
+    #[derive(Entity)]
+    #[graphix(table_name = "users", edge(to = Group, field = "group_id", on_delete = "set_null"))]
+    #[allow(dead_code)]
+    pub struct User {
+        #[graphix(primary_key)]
+        id: i64,
+        name: String,
+        #[graphix(unique)]
+        email: String,
+        group_id: Option<i64>,
+        status: Status,
+    }
+
+    #[derive(Entity)]
+    #[graphix(table_name = "legacy")]
+    #[allow(dead_code)]
+    pub struct Legacy {
+        #[graphix(primary_key)]
+        id: i32,
+    }
+}
+
+mod v2 {
+    use graphix::{Entity, SqlEnum};
+
+    #[derive(SqlEnum)]
+    #[allow(dead_code)]
+    pub enum Status {
+        Active,
+        Banned,
+        Deleted,
+    }
+
+    #[derive(Entity)]
+    #[graphix(table_name = "groups")]
+    #[allow(dead_code)]
+    pub struct Group {
+        #[graphix(primary_key)]
+        id: i64,
+        name: String,
+    }
+
+    #[derive(Entity)]
+    #[graphix(
+        table_name = "users",
+        edge(to = Group, field = "group_id", on_delete = "cascade"),
+        index(columns = ["name"])
+    )]
+    #[allow(dead_code)]
+    pub struct User {
+        #[graphix(primary_key)]
+        id: i64,
+        name: Option<String>,
+        group_id: i64,
+        status: Status,
+        #[graphix(default = 0)]
+        age: i32,
+    }
+
+    #[derive(Entity)]
+    #[graphix(table_name = "posts", edge(to = User, field = "author_id"))]
+    #[allow(dead_code)]
+    pub struct Post {
+        #[graphix(primary_key)]
+        id: i64,
+        author_id: i64,
+    }
+}
+
+fn v1() -> Schema {
+    [
+        v1::Group::descriptor(),
+        v1::User::descriptor(),
+        v1::Legacy::descriptor(),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn v2() -> Schema {
+    [
+        v2::Group::descriptor(),
+        v2::User::descriptor(),
+        v2::Post::descriptor(),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn test_no_changes() {
+    assert!(SchemaDiff::between(&v1(), &v1()).is_empty());
+}
+
+#[test]
+fn test_changes() {
+    let diff = SchemaDiff::between(&v1(), &v2());
+
+    assert!(diff.changes.iter().any(|c| matches!(
+        c,
+        Change::AlterColumnNullable { column, nullable: true, .. } if column == "name"
+    )));
+    assert!(diff.changes.iter().any(|c| matches!(
+        c,
+        Change::DropColumn { column, .. } if column == "email"
+    )));
+    assert!(diff.changes.iter().any(|c| matches!(
+        c,
+        Change::DropTable(t) if t.table_name == "legacy"
+    )));
+}
+
+#[test]
+fn test_diff_statements() {
+    assert_eq!(
+        ddl::diff_statements(&SchemaDiff::between(&v1(), &v2())),
+        vec![
+            "ALTER TYPE \"public\".\"status\" ADD VALUE 'deleted';",
+            "ALTER TABLE \"public\".\"users\" DROP CONSTRAINT \"users_group_id_fkey\";",
+            "DROP INDEX \"public\".\"idx_users_email_unique\";",
+            "DROP TABLE \"public\".\"legacy\";",
+            "CREATE TABLE \"public\".\"posts\" (\n  \"id\" bigint NOT NULL,\n  \"author_id\" bigint NOT NULL,\n  PRIMARY KEY (\"id\")\n);",
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"name\" DROP NOT NULL;",
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"group_id\" SET NOT NULL;",
+            "ALTER TABLE \"public\".\"users\" ADD COLUMN \"age\" integer NOT NULL DEFAULT 0;",
+            "ALTER TABLE \"public\".\"users\" DROP COLUMN \"email\";",
+            "CREATE INDEX \"idx_users_name\" ON \"public\".\"users\" (\"name\");",
+            "ALTER TABLE \"public\".\"users\" ADD CONSTRAINT \"users_group_id_fkey\" FOREIGN KEY (\"group_id\") REFERENCES \"public\".\"groups\" (\"id\") ON DELETE CASCADE;",
+            "ALTER TABLE \"public\".\"posts\" ADD CONSTRAINT \"posts_author_id_fkey\" FOREIGN KEY (\"author_id\") REFERENCES \"public\".\"users\" (\"id\");",
+        ]
+    );
+}
+
+#[test]
+fn test_reverse_diff() {
+    // values can't be removed from an enum, so it is recreated and its columns converted
+    assert_eq!(
+        ddl::diff_statements(&SchemaDiff::between(&v2(), &v1())),
+        vec![
+            "ALTER TYPE \"public\".\"status\" RENAME TO \"status_old\";",
+            "CREATE TYPE \"public\".\"status\" AS ENUM ('active', 'banned');",
+            "ALTER TABLE \"public\".\"users\" DROP CONSTRAINT \"users_group_id_fkey\";",
+            "DROP INDEX \"public\".\"idx_users_name\";",
+            "DROP TABLE \"public\".\"posts\";",
+            "CREATE TABLE \"public\".\"legacy\" (\n  \"id\" integer NOT NULL,\n  PRIMARY KEY (\"id\")\n);",
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"name\" SET NOT NULL;",
+            "ALTER TABLE \"public\".\"users\" ADD COLUMN \"email\" text NOT NULL;",
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"group_id\" DROP NOT NULL;",
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"status\" TYPE \"public\".\"status\" USING \"status\"::text::\"public\".\"status\";",
+            "ALTER TABLE \"public\".\"users\" DROP COLUMN \"age\";",
+            "CREATE UNIQUE INDEX \"idx_users_email_unique\" ON \"public\".\"users\" (\"email\");",
+            "ALTER TABLE \"public\".\"users\" ADD CONSTRAINT \"users_group_id_fkey\" FOREIGN KEY (\"group_id\") REFERENCES \"public\".\"groups\" (\"id\") ON DELETE SET NULL;",
+            "DROP TYPE \"public\".\"status_old\";",
+        ]
+    );
+}
+
+#[test]
+fn test_alter_column_type_using() {
+    let change = |from, to| {
+        ddl::change_sql(&Change::AlterColumnType {
+            table: v1::User::descriptor().clone(),
+            column: "name".to_string(),
+            from,
+            to,
+        })
+    };
+
+    // text has no automatic cast to integer
+    assert_eq!(
+        change(LogicalType::Text, LogicalType::Int),
+        "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"name\" TYPE integer USING \"name\"::integer;"
+    );
+    assert_eq!(
+        change(LogicalType::Text, LogicalType::Json),
+        "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"name\" TYPE jsonb USING \"name\"::jsonb;"
+    );
+    assert_eq!(
+        change(LogicalType::Int, LogicalType::BigInt),
+        "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"name\" TYPE bigint;"
+    );
+}
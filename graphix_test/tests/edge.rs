@@ -0,0 +1,227 @@
+use graphix::descriptor::{EdgeCardinality, ReferenceAction};
+use graphix::entity::Entity;
+use graphix::{EdgeSchema, Entity};
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "groups",
+    edge(from = User, field = "group_id"),
+    edge(from = User, m2m, name = "members", through = "memberships")
+)]
+#[allow(dead_code)]
+struct Group {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "users",
+    edge(to = Group, field = "group_id", on_delete = "set_null"),
+    edge(to = Self, field = "manager_id", name = "manager"),
+    edge(from = Passport, field = "owner_id", unique),
+    edge(to = Group, m2m, name = "groups", through = "memberships", edge_schema = Membership),
+    edge(to = Self, m2m, name = "friends")
+)]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    group_id: Option<i64>,
+    manager_id: Option<i64>,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "passports",
+    edge(to = User, field = "owner_id", name = "owner", unique, on_delete = "cascade", on_update = "no_action")
+)]
+#[allow(dead_code)]
+struct Passport {
+    #[graphix(primary_key)]
+    id: i64,
+    owner_id: i64,
+}
+
+#[derive(EdgeSchema)]
+#[allow(dead_code)]
+struct Membership {
+    #[graphix(default_expr = "now()")]
+    joined_at: i64,
+    role: Option<String>,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        group_id: None,
+        manager_id: None,
+    }
+}
+
+#[test]
+fn test_many_to_one() {
+    let desc = user().entity_descriptor();
+    let edge = &desc.edges[0];
+
+    assert_eq!(edge.name, "group");
+    assert_eq!(edge.target, "Group");
+    assert_eq!(edge.target_table, "groups");
+    assert_eq!(edge.cardinality, EdgeCardinality::M2O);
+    assert!(!edge.inverse);
+    assert_eq!(edge.columns, vec!["group_id"]);
+    assert_eq!(edge.ref_columns, vec!["id"]);
+    assert_eq!(edge.on_delete, Some(ReferenceAction::SetNull));
+    assert_eq!(edge.on_update, None);
+}
+
+#[test]
+fn test_self_edge() {
+    let desc = user().entity_descriptor();
+    let edge = &desc.edges[1];
+
+    assert_eq!(edge.name, "manager");
+    assert_eq!(edge.target_table, "users");
+    assert_eq!(edge.cardinality, EdgeCardinality::M2O);
+}
+
+#[test]
+fn test_inverse_edges() {
+    let group = Group {
+        id: 1,
+        name: String::new(),
+    };
+    let edge = &group.entity_descriptor().edges[0];
+    assert_eq!(edge.name, "users");
+    assert_eq!(edge.cardinality, EdgeCardinality::O2M);
+    assert!(edge.inverse);
+    assert_eq!(edge.columns, vec!["group_id"]);
+    assert_eq!(edge.ref_columns, vec!["id"]);
+
+    let edge = &user().entity_descriptor().edges[2];
+    assert_eq!(edge.name, "passport");
+    assert_eq!(edge.cardinality, EdgeCardinality::O2O);
+
+    // inverse edges never produce a foreign key
+    let hcl = hcl::to_string(&group.as_atlas_hcl()).unwrap();
+    assert!(!hcl.contains("foreign_key"));
+}
+
+#[test]
+fn test_one_to_one() {
+    let passport = Passport { id: 1, owner_id: 1 };
+    let desc = passport.entity_descriptor();
+
+    assert_eq!(desc.edges[0].cardinality, EdgeCardinality::O2O);
+    assert_eq!(desc.indexes[0].name, "idx_passports_owner_id_unique");
+    assert!(desc.indexes[0].unique);
+}
+
+#[test]
+fn test_foreign_key_atlas_hcl() {
+    let hcl = hcl::to_string(&user().as_atlas_hcl()).unwrap();
+    assert!(hcl.contains(
+        "  foreign_key \"users_group_id_fkey\" {\n    columns = [\n      column.group_id\n    ]\n    ref_columns = [\n      table.groups.column.id\n    ]\n    on_delete = SET_NULL\n  }"
+    ));
+    assert!(hcl.contains("ref_columns = [\n      table.users.column.id\n    ]"));
+
+    let hcl = hcl::to_string(&Passport { id: 1, owner_id: 1 }.as_atlas_hcl()).unwrap();
+    assert!(hcl.contains("on_update = NO_ACTION\n    on_delete = CASCADE"));
+}
+
+#[test]
+fn test_cross_schema_foreign_key_atlas_hcl() {
+    #[derive(Entity)]
+    #[graphix(
+        table_name = "sessions",
+        schema_name = "schema.auth",
+        edge(to = User, field = "user_id")
+    )]
+    #[allow(dead_code)]
+    struct Session {
+        #[graphix(primary_key)]
+        id: i64,
+        user_id: i64,
+    }
+
+    // the users table is in another schema, so the reference names it
+    let hcl = hcl::to_string(&Session { id: 1, user_id: 1 }.as_atlas_hcl()).unwrap();
+    assert!(hcl.contains("ref_columns = [\n      table.public.users.column.id\n    ]"));
+}
+
+#[test]
+fn test_many_to_many() {
+    let desc = user().entity_descriptor();
+    let edge = &desc.edges[3];
+
+    assert_eq!(edge.name, "groups");
+    assert_eq!(edge.cardinality, EdgeCardinality::M2M);
+    assert!(!edge.inverse);
+    assert_eq!(edge.columns, vec!["group_id"]);
+    assert_eq!(edge.ref_columns, vec!["id"]);
+    assert_eq!(edge.through.as_deref(), Some("memberships"));
+
+    let group = Group {
+        id: 1,
+        name: String::new(),
+    };
+    let edge = &group.entity_descriptor().edges[1];
+    assert_eq!(edge.name, "members");
+    assert_eq!(edge.cardinality, EdgeCardinality::M2M);
+    assert!(edge.inverse);
+    assert_eq!(edge.columns, vec!["user_id"]);
+    assert_eq!(edge.through.as_deref(), Some("memberships"));
+
+    // only the owning side generates the join table
+    assert!(group.entity_descriptor().join_tables.is_empty());
+}
+
+#[test]
+fn test_join_table() {
+    let desc = user().entity_descriptor();
+    assert_eq!(desc.join_tables.len(), 2);
+
+    let join = &desc.join_tables[0];
+    assert_eq!(join.table_name, "memberships");
+    assert_eq!(join.schema_name, "schema.public");
+    assert_eq!(join.primary_key, vec!["user_id", "group_id"]);
+    assert_eq!(
+        join.fields
+            .iter()
+            .map(|f| f.column_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["user_id", "group_id", "joined_at", "role"]
+    );
+    assert!(join.fields[3].nullable);
+    assert_eq!(join.edges[0].target_table, "users");
+    assert_eq!(join.edges[1].target_table, "groups");
+    assert!(join
+        .edges
+        .iter()
+        .all(|e| e.on_delete == Some(ReferenceAction::Cascade)));
+
+    // self referencing edges take the target's column prefix from the edge name
+    let join = &desc.join_tables[1];
+    assert_eq!(join.table_name, "users_users");
+    assert_eq!(join.primary_key, vec!["user_id", "friends_id"]);
+}
+
+#[test]
+fn test_join_table_atlas_hcl() {
+    // m2m edges have no foreign key on the entity's own table
+    let hcl = hcl::to_string(&user().as_atlas_hcl()).unwrap();
+    assert!(!hcl.contains("memberships"));
+
+    let hcl = hcl::to_string(&user().as_atlas_body()).unwrap();
+    assert!(hcl.contains("table \"users\" {"));
+    assert!(hcl.contains("table \"memberships\" {"));
+    assert!(hcl.contains("table \"users_users\" {"));
+    assert!(hcl.contains(
+        "  primary_key {\n    columns = [\n      column.user_id,\n      column.group_id\n    ]\n  }"
+    ));
+    assert!(hcl.contains(
+        "  foreign_key \"memberships_group_id_fkey\" {\n    columns = [\n      column.group_id\n    ]\n    ref_columns = [\n      table.groups.column.id\n    ]\n    on_delete = CASCADE\n  }"
+    ));
+    assert!(hcl.contains("  column \"joined_at\" {\n    type = bigint\n    null = false\n    default = sql(\"now()\")\n  }"));
+}
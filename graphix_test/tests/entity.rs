@@ -0,0 +1,394 @@
+use graphix::entity::Entity;
+use graphix::sql::postgres::ColumnType;
+use graphix::sql::LogicalType;
+use graphix::Entity;
+
+#[derive(Entity)]
+#[allow(dead_code)]
+struct Profile {
+    #[graphix(primary_key)]
+    id: i32,
+    bio: Option<String>,
+    #[graphix(nullable)]
+    age: Option<i16>,
+}
+
+#[test]
+fn test_option_fields_are_nullable() {
+    let p = Profile {
+        id: 1,
+        bio: None,
+        age: None,
+    };
+    let desc = p.entity_descriptor();
+
+    assert!(!desc.fields[0].nullable);
+    assert!(matches!(desc.fields[0].sql_type, LogicalType::Int));
+
+    assert!(desc.fields[1].nullable);
+    assert!(matches!(desc.fields[1].sql_type, LogicalType::Text));
+
+    assert!(desc.fields[2].nullable);
+    assert!(matches!(desc.fields[2].sql_type, LogicalType::SmallInt));
+}
+
+#[derive(Entity)]
+#[allow(dead_code)]
+struct Sample {
+    #[graphix(primary_key)]
+    id: i32,
+    tags: Vec<String>,
+    weights: [f64; 3],
+    grid: Vec<Vec<i32>>,
+    payload: Vec<u8>,
+    scores: Option<Vec<i64>>,
+}
+
+#[test]
+fn test_array_fields() {
+    let s = Sample {
+        id: 1,
+        tags: vec![],
+        weights: [0.0; 3],
+        grid: vec![],
+        payload: vec![],
+        scores: None,
+    };
+    let desc = s.entity_descriptor();
+    let types: Vec<String> = desc.fields.iter().map(|f| f.sql_type.to_string()).collect();
+    assert_eq!(
+        types,
+        vec![
+            "integer",
+            "text[]",
+            "double_precision[]",
+            "integer[][]",
+            "bytea",
+            "bigint[]",
+        ]
+    );
+    assert!(desc.fields[5].nullable);
+
+    let hcl = hcl::to_string(&s.as_atlas_hcl()).unwrap();
+    assert!(hcl.contains("type = sql(\"text[]\")"));
+    assert!(hcl.contains("type = sql(\"integer[][]\")"));
+}
+
+#[derive(Entity)]
+#[allow(dead_code)]
+struct Invoice {
+    #[graphix(primary_key, sql_type = "varchar(255)")]
+    number: String,
+    #[graphix(sql_type = "numeric(12, 2)")]
+    total: f64,
+    #[graphix(sql_type = "timestamp with time zone")]
+    paid_at: Option<i64>,
+    #[graphix(sql_type = "jsonb")]
+    metadata: String,
+}
+
+#[test]
+fn test_sql_type_override() {
+    let i = Invoice {
+        number: String::new(),
+        total: 0.0,
+        paid_at: None,
+        metadata: String::new(),
+    };
+    let desc = i.entity_descriptor();
+    let types: Vec<String> = desc.fields.iter().map(|f| f.sql_type.to_string()).collect();
+    assert_eq!(
+        types,
+        vec!["varchar(255)", "numeric(12,2)", "timestamptz", "jsonb"]
+    );
+    assert!(desc.fields[2].nullable);
+}
+
+type Email = String;
+
+#[allow(dead_code)]
+struct Cents(i64);
+
+impl graphix::sql::postgres::SqlType for Cents {
+    fn column_type() -> ColumnType {
+        ColumnType::Money
+    }
+}
+
+#[derive(Entity)]
+#[graphix(no_primary_key)]
+#[allow(dead_code)]
+struct Customer {
+    name: std::string::String,
+    email: Email,
+    balance: Cents,
+    credit: Option<Cents>,
+}
+
+#[test]
+fn test_sql_type_resolution() {
+    let c = Customer {
+        name: String::new(),
+        email: String::new(),
+        balance: Cents(0),
+        credit: None,
+    };
+    let desc = c.entity_descriptor();
+    let types: Vec<String> = desc.fields.iter().map(|f| f.sql_type.to_string()).collect();
+    assert_eq!(types, vec!["text", "text", "money", "money"]);
+    assert!(!desc.fields[2].nullable);
+    assert!(desc.fields[3].nullable);
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "memberships", primary_key = ["org_id", "member"])]
+#[allow(dead_code)]
+struct Membership {
+    org_id: i64,
+    #[graphix(colname = "user_id")]
+    member: i64,
+    role: String,
+}
+
+#[test]
+fn test_primary_key() {
+    let p = Profile {
+        id: 1,
+        bio: None,
+        age: None,
+    };
+    assert_eq!(p.entity_descriptor().primary_key, vec!["id"]);
+
+    let c = Customer {
+        name: String::new(),
+        email: String::new(),
+        balance: Cents(0),
+        credit: None,
+    };
+    assert!(c.entity_descriptor().primary_key.is_empty());
+    assert!(!hcl::to_string(&c.as_atlas_hcl())
+        .unwrap()
+        .contains("primary_key"));
+}
+
+#[test]
+fn test_composite_primary_key() {
+    let m = Membership {
+        org_id: 1,
+        member: 2,
+        role: String::new(),
+    };
+    assert_eq!(m.entity_descriptor().primary_key, vec!["org_id", "user_id"]);
+
+    let hcl = hcl::to_string(&m.as_atlas_hcl()).unwrap();
+    assert!(hcl.contains(
+        "  primary_key {\n    columns = [\n      column.org_id,\n      column.user_id\n    ]\n  }"
+    ));
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "documents",
+    index(columns = ["owner_id", "slug"], unique),
+    index(columns = ["tags"], method = "gin"),
+    index(columns = ["owner_id"], predicate = "deleted_at IS NULL", name = "idx_live_documents"),
+    index(parts = ["owner_id", expr("lower(title)")], name = "idx_documents_title")
+)]
+#[allow(dead_code)]
+struct Document {
+    #[graphix(primary_key)]
+    id: i64,
+    owner_id: i64,
+    #[graphix(unique)]
+    slug: String,
+    title: String,
+    tags: Vec<String>,
+    deleted_at: Option<i64>,
+}
+
+fn document() -> Document {
+    Document {
+        id: 1,
+        owner_id: 1,
+        slug: String::new(),
+        title: String::new(),
+        tags: vec![],
+        deleted_at: None,
+    }
+}
+
+#[test]
+fn test_indexes() {
+    use graphix::descriptor::{IndexMethod, IndexPart};
+
+    let desc = document().entity_descriptor();
+    let names: Vec<&str> = desc.indexes.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "idx_documents_slug_unique",
+            "idx_documents_owner_id_slug_unique",
+            "idx_documents_tags",
+            "idx_live_documents",
+            "idx_documents_title",
+        ]
+    );
+
+    assert!(desc.indexes[1].unique);
+    assert_eq!(desc.indexes[2].method, Some(IndexMethod::Gin));
+    assert!(!desc.indexes[2].unique);
+    assert_eq!(
+        desc.indexes[3].predicate.as_deref(),
+        Some("deleted_at IS NULL")
+    );
+    assert_eq!(
+        desc.indexes[4].parts,
+        vec![
+            IndexPart::Column("owner_id".to_string()),
+            IndexPart::Expr("lower(title)".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_indexes_atlas_hcl() {
+    let hcl = hcl::to_string(&document().as_atlas_hcl()).unwrap();
+
+    assert!(hcl.contains(
+        "  index \"idx_documents_owner_id_slug_unique\" {\n    unique = true\n    columns = [\n      column.owner_id,\n      column.slug\n    ]\n  }"
+    ));
+    assert!(hcl.contains("  index \"idx_documents_tags\" {\n    type = GIN\n"));
+    assert!(hcl.contains("    where = \"deleted_at IS NULL\"\n"));
+    assert!(hcl.contains(
+        "    on {\n      column = column.owner_id\n    }\n\n    on {\n      expr = \"lower(title)\"\n    }\n"
+    ));
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "accounts",
+    index(parts = [expr("lower(name)"), "id"], unique, name = "idx_accounts_name")
+)]
+#[allow(dead_code)]
+struct Account {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+}
+
+#[test]
+fn test_index_parts_order() {
+    use graphix::descriptor::IndexPart;
+
+    let account = Account {
+        id: 1,
+        name: String::new(),
+    };
+
+    // expressions can come before columns
+    assert_eq!(
+        account.entity_descriptor().indexes[0].parts,
+        vec![
+            IndexPart::Expr("lower(name)".to_string()),
+            IndexPart::Column("id".to_string()),
+        ]
+    );
+}
+
+#[derive(Entity)]
+#[allow(dead_code)]
+struct Task {
+    #[graphix(primary_key, default_expr = "gen_random_uuid()")]
+    id: uuid::Uuid,
+    #[graphix(default = false)]
+    done: bool,
+    #[graphix(default = -1)]
+    priority: i32,
+    #[graphix(default = 0.5)]
+    weight: f64,
+    #[graphix(default = "todo")]
+    title: Option<String>,
+    #[graphix(sql_type = "varchar(16)", default = "inbox")]
+    list: String,
+    #[graphix(default_expr = "now()")]
+    created_at: i64,
+}
+
+#[test]
+fn test_defaults() {
+    use graphix::descriptor::ColumnDefault;
+
+    let t = Task {
+        id: uuid::Uuid::nil(),
+        done: false,
+        priority: 0,
+        weight: 0.0,
+        title: None,
+        list: String::new(),
+        created_at: 0,
+    };
+    let defaults: Vec<Option<ColumnDefault>> = t
+        .entity_descriptor()
+        .fields
+        .into_iter()
+        .map(|f| f.default)
+        .collect();
+    assert_eq!(
+        defaults,
+        vec![
+            Some(ColumnDefault::Expr("gen_random_uuid()".to_string())),
+            Some(ColumnDefault::Bool(false)),
+            Some(ColumnDefault::Int(-1)),
+            Some(ColumnDefault::Float("0.5".to_string())),
+            Some(ColumnDefault::Str("todo".to_string())),
+            Some(ColumnDefault::Str("inbox".to_string())),
+            Some(ColumnDefault::Expr("now()".to_string())),
+        ]
+    );
+
+    let hcl = hcl::to_string(&t.as_atlas_hcl()).unwrap();
+    assert!(hcl.contains("default = sql(\"gen_random_uuid()\")"));
+    assert!(hcl.contains("default = false"));
+    assert!(hcl.contains("default = -1"));
+    assert!(hcl.contains("default = 0.5"));
+    assert!(hcl.contains("default = \"todo\""));
+    assert!(hcl.contains("default = sql(\"now()\")"));
+}
+
+#[test]
+fn test_static_descriptor() {
+    let desc = Profile::descriptor();
+    assert_eq!(desc.table_name, "profiles");
+
+    // built once and shared between calls
+    assert!(std::ptr::eq(desc, Profile::descriptor()));
+
+    let p = Profile {
+        id: 1,
+        bio: None,
+        age: None,
+    };
+    assert_eq!(&p.entity_descriptor(), desc);
+}
+
+#[test]
+fn test_entity_trait_object() {
+    let entities: Vec<Box<dyn Entity>> = vec![
+        Box::new(Profile {
+            id: 1,
+            bio: None,
+            age: None,
+        }),
+        Box::new(Membership {
+            org_id: 1,
+            member: 1,
+            role: String::new(),
+        }),
+    ];
+
+    let tables: Vec<String> = entities
+        .iter()
+        .map(|e| e.entity_descriptor().table_name)
+        .collect();
+    assert_eq!(tables, vec!["profiles", "memberships"]);
+}
@@ -0,0 +1,107 @@
+use graphix::diff::SchemaDiff;
+use graphix::entity::Entity;
+use graphix::migrate::{MigrateError, MigrationDir, SUM_FILE};
+use graphix::schema::Schema;
+use graphix::Entity;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Entity)]
+#[graphix(table_name = "users")]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graphix_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn init_diff() -> SchemaDiff {
+    SchemaDiff::between(&Schema::new(), &[User::descriptor()].into_iter().collect())
+}
+
+#[test]
+fn test_atlas_sum_format() {
+    let path = temp_dir("sum");
+    fs::create_dir_all(&path).unwrap();
+    fs::write(
+        path.join("20240101000000_init.sql"),
+        "CREATE TABLE t (id int);\n",
+    )
+    .unwrap();
+    fs::write(path.join("20240102000000_drop.sql"), "DROP TABLE t;\n").unwrap();
+    fs::write(path.join("README.md"), "not a migration").unwrap();
+
+    assert_eq!(
+        MigrationDir::new(&path).hash_file().unwrap().to_string(),
+        "h1:Ny0EcV6+Eo+UomfL5OqrhboMy/BZLVaYr1rNpDwJ5Tg=\n\
+         20240101000000_init.sql h1:SHzK9HMd1VJXxyfkB4DdEsZDHvMZKf0Wuy5nmE70+m8=\n\
+         20240102000000_drop.sql h1:pf1DPJ2NnS/0fkF++wE2ztGz4qPg5TobuutFQz3N8ic=\n"
+    );
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn test_write() {
+    let path = temp_dir("write");
+    let dir = MigrationDir::new(&path);
+
+    let file = dir
+        .write("20240101000000", "init", &init_diff())
+        .unwrap()
+        .unwrap();
+    assert_eq!(file, path.join("20240101000000_init.sql"));
+    assert!(fs::read_to_string(&file)
+        .unwrap()
+        .starts_with("CREATE SCHEMA IF NOT EXISTS \"public\";\nCREATE TABLE \"public\".\"users\""));
+    assert_eq!(
+        fs::read_to_string(path.join(SUM_FILE)).unwrap(),
+        dir.hash_file().unwrap().to_string()
+    );
+    dir.verify().unwrap();
+
+    // regenerating the same migration leaves the directory as it is
+    let sum = fs::read_to_string(path.join(SUM_FILE)).unwrap();
+    assert_eq!(
+        dir.write("20240101000000", "init", &init_diff()).unwrap(),
+        Some(file)
+    );
+    assert_eq!(fs::read_to_string(path.join(SUM_FILE)).unwrap(), sum);
+
+    // nothing to migrate
+    assert_eq!(
+        dir.write("20240102000000", "noop", &SchemaDiff::default())
+            .unwrap(),
+        None
+    );
+    assert_eq!(dir.files().unwrap().len(), 1);
+
+    fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn test_detects_edits() {
+    let path = temp_dir("edits");
+    let dir = MigrationDir::new(&path);
+    dir.write("20240101000000", "init", &init_diff()).unwrap();
+
+    fs::write(path.join("20240101000000_init.sql"), "DROP TABLE users;\n").unwrap();
+    assert!(matches!(
+        dir.verify(),
+        Err(MigrateError::ChecksumMismatch { file: Some(f) }) if f == "20240101000000_init.sql"
+    ));
+    assert!(dir.write("20240102000000", "next", &init_diff()).is_err());
+
+    // a directory that lost its sum file is caught as well
+    fs::write(path.join("20240101000000_init.sql"), "").unwrap();
+    fs::remove_file(path.join(SUM_FILE)).unwrap();
+    assert!(matches!(dir.verify(), Err(MigrateError::MissingSum)));
+
+    fs::remove_dir_all(path).unwrap();
+}
@@ -0,0 +1,200 @@
+use graphix::mutation::MutationError;
+use graphix::query::Value;
+use graphix::Entity;
+
+#[derive(Entity)]
+#[graphix(table_name = "users")]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key, sql_type = "bigserial")]
+    id: i64,
+    #[graphix(unique, immutable)]
+    email: String,
+    name: String,
+    nickname: Option<String>,
+    #[graphix(default = 0)]
+    age: i32,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "members", primary_key = ["group_id", "user_id"])]
+#[allow(dead_code)]
+struct Member {
+    group_id: i64,
+    user_id: i64,
+    #[graphix(immutable)]
+    joined_at: time::OffsetDateTime,
+}
+
+const COLUMNS: &str = "\"id\", \"email\", \"name\", \"nickname\", \"age\"";
+
+#[test]
+fn test_create() {
+    let (sql, params) = User::create()
+        .set_email("ann@example.com")
+        .set_name("ann")
+        .set_nickname("annie")
+        .to_sql()
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        format!(
+            "INSERT INTO \"public\".\"users\" (\"email\", \"name\", \"nickname\") \
+             VALUES ($1, $2, $3) RETURNING {}",
+            COLUMNS
+        )
+    );
+    assert_eq!(
+        params,
+        ["ann@example.com", "ann", "annie"]
+            .map(|p| Value::Text(p.to_string()))
+            .to_vec()
+    );
+}
+
+#[test]
+fn test_create_missing_field() {
+    // the serial id, defaulted age and nullable nickname can be left out
+    let err = User::create()
+        .set_email("ann@example.com")
+        .to_sql()
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        MutationError::MissingField {
+            entity: "User",
+            field: "name",
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "creating `User` requires a value for `name`"
+    );
+}
+
+#[test]
+fn test_update_one() {
+    let (sql, params) = User::update_one(7)
+        .set_name("bob")
+        .set_age(30)
+        .set_name("bobby")
+        .to_sql()
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        format!(
+            "UPDATE \"public\".\"users\" SET \"name\" = $1, \"age\" = $2 WHERE \"id\" = $3 \
+             RETURNING {}",
+            COLUMNS
+        )
+    );
+    assert_eq!(
+        params,
+        vec![
+            Value::Text("bobby".to_string()),
+            Value::Int(30),
+            Value::BigInt(7)
+        ]
+    );
+}
+
+#[test]
+fn test_update() {
+    let (sql, params) = User::update()
+        .filter(UserColumns::age().lt(18))
+        .filter(UserColumns::nickname().is_not_null())
+        .clear_nickname()
+        .to_sql()
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        "UPDATE \"public\".\"users\" SET \"nickname\" = $1 \
+         WHERE \"age\" < $2 AND \"nickname\" IS NOT NULL"
+    );
+    assert_eq!(params, vec![Value::Null, Value::Int(18)]);
+
+    assert_eq!(
+        User::update().to_sql().unwrap_err(),
+        MutationError::NoChanges { entity: "User" }
+    );
+}
+
+#[test]
+fn test_composite_key() {
+    // no `update_one`, and the immutable `joined_at` has no update setter
+    let (sql, params) = Member::update()
+        .set_group_id(2)
+        .filter(MemberColumns::user_id().eq(1))
+        .to_sql()
+        .unwrap();
+
+    assert_eq!(
+        sql,
+        "UPDATE \"public\".\"members\" SET \"group_id\" = $1 WHERE \"user_id\" = $2"
+    );
+    assert_eq!(params, vec![Value::BigInt(2), Value::BigInt(1)]);
+}
+
+#[test]
+fn test_delete() {
+    let (sql, params) = User::delete()
+        .filter(UserColumns::id().eq(7))
+        .to_sql()
+        .unwrap();
+
+    assert_eq!(sql, "DELETE FROM \"public\".\"users\" WHERE \"id\" = $1");
+    assert_eq!(params, vec![Value::BigInt(7)]);
+}
+
+#[test]
+fn test_no_filter() {
+    // leaving out the filter has to be spelled out with `all()`
+    assert_eq!(
+        User::update().set_age(0).to_sql().unwrap_err(),
+        MutationError::NoFilter { entity: "User" }
+    );
+    assert_eq!(
+        User::delete().to_sql().unwrap_err(),
+        MutationError::NoFilter { entity: "User" }
+    );
+
+    let (sql, params) = User::update().set_age(0).all().to_sql().unwrap();
+    assert_eq!(sql, "UPDATE \"public\".\"users\" SET \"age\" = $1");
+    assert_eq!(params, vec![Value::Int(0)]);
+
+    let (sql, params) = User::delete().all().to_sql().unwrap();
+    assert_eq!(sql, "DELETE FROM \"public\".\"users\"");
+    assert!(params.is_empty());
+}
+
+#[test]
+fn test_get_by_unique_field() {
+    let (sql, params) = User::get_by_email("ann@example.com").to_sql();
+
+    assert_eq!(
+        sql,
+        format!(
+            "SELECT {} FROM \"public\".\"users\" WHERE \"email\" = $1 LIMIT 1",
+            COLUMNS
+        )
+    );
+    assert_eq!(params, vec![Value::Text("ann@example.com".to_string())]);
+}
+
+#[test]
+fn test_get_by_primary_key() {
+    let (sql, params) = User::get_by_id(7).to_sql();
+
+    assert_eq!(
+        sql,
+        format!(
+            "SELECT {} FROM \"public\".\"users\" WHERE \"id\" = $1 LIMIT 1",
+            COLUMNS
+        )
+    );
+    assert_eq!(params, vec![Value::BigInt(7)]);
+}
@@ -0,0 +1,268 @@
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::sql::mysql::atlas::{schema_hcl, table_hcl};
+use graphix::sql::mysql::ddl;
+use graphix::sql::mysql::{Charset, ColumnType};
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Status {
+    Active,
+    Banned,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "groups", edge(from = User, field = "group_id"))]
+#[allow(dead_code)]
+struct Group {
+    #[graphix(primary_key)]
+    id: u32,
+    #[graphix(unique)]
+    name: String,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "users",
+    edge(to = Group, field = "group_id", on_delete = "set_null"),
+    edge(to = Group, m2m, through = "moderators")
+)]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: uuid::Uuid,
+    bio: String,
+    #[graphix(default = "active")]
+    status: Status,
+    #[graphix(default = true)]
+    verified: bool,
+    #[graphix(default_expr = "now()")]
+    created_at: time::OffsetDateTime,
+    group_id: Option<u32>,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "documents", index(columns = ["body"], method = "gin"))]
+#[allow(dead_code)]
+struct Document {
+    #[graphix(primary_key)]
+    id: i64,
+    tags: Vec<String>,
+    #[graphix(sql_type = "tstzrange")]
+    published: String,
+    #[graphix(sql_type = "tsvector")]
+    body: String,
+}
+
+fn hcl(schema: &Schema) -> String {
+    hcl::to_string(&schema_hcl(schema, &Charset::default()).unwrap()).unwrap()
+}
+
+#[test]
+fn test_table_hcl() {
+    let table = table_hcl(User::descriptor(), &Charset::default()).unwrap();
+    let hcl = hcl::to_string(&hcl::Body::builder().add_block(table).build()).unwrap();
+
+    assert_eq!(
+        hcl,
+        r#"table "users" {
+  schema = schema.public
+  charset = "utf8mb4"
+  collate = "utf8mb4_0900_ai_ci"
+
+  column "id" {
+    type = char(36)
+    null = false
+  }
+
+  column "bio" {
+    type = longtext
+    null = false
+  }
+
+  column "status" {
+    type = enum("active", "banned")
+    null = false
+    default = "active"
+  }
+
+  column "verified" {
+    type = bool
+    null = false
+    default = true
+  }
+
+  column "created_at" {
+    type = timestamp(6)
+    null = false
+    default = sql("CURRENT_TIMESTAMP(6)")
+  }
+
+  column "group_id" {
+    type = int
+    unsigned = true
+    null = true
+  }
+
+  primary_key {
+    columns = [
+      column.id
+    ]
+  }
+
+  foreign_key "users_group_id_fkey" {
+    columns = [
+      column.group_id
+    ]
+    ref_columns = [
+      table.groups.column.id
+    ]
+    on_delete = SET_NULL
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn test_schema_hcl() {
+    let schema: Schema = [Group::descriptor(), User::descriptor()]
+        .into_iter()
+        .collect();
+    let hcl = hcl(&schema);
+
+    assert!(hcl.starts_with(
+        "schema \"public\" {\n  charset = \"utf8mb4\"\n  collate = \"utf8mb4_0900_ai_ci\"\n}\n"
+    ));
+    // enums are inline, the join table is a table of its own
+    assert!(!hcl.contains("enum \"status\""));
+    assert!(hcl.contains("table \"moderators\""));
+    // a unique text column can't be a longtext
+    assert!(hcl.contains("column \"name\" {\n    type = varchar(255)"));
+}
+
+#[test]
+fn test_charset() {
+    let charset = Charset {
+        charset: "latin1".to_string(),
+        collate: "latin1_swedish_ci".to_string(),
+    };
+    let schema: Schema = [Group::descriptor()].into_iter().collect();
+    let hcl = hcl::to_string(&schema_hcl(&schema, &charset).unwrap()).unwrap();
+
+    assert_eq!(hcl.matches("charset = \"latin1\"").count(), 2);
+    assert_eq!(hcl.matches("collate = \"latin1_swedish_ci\"").count(), 2);
+}
+
+#[test]
+fn test_create_table() {
+    assert_eq!(
+        ddl::create_table(User::descriptor(), &Charset::default()).unwrap(),
+        "CREATE TABLE `users` (\n  \
+           `id` char(36) NOT NULL,\n  \
+           `bio` longtext NOT NULL,\n  \
+           `status` enum('active','banned') NOT NULL DEFAULT 'active',\n  \
+           `verified` bool NOT NULL DEFAULT true,\n  \
+           `created_at` timestamp(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),\n  \
+           `group_id` int unsigned,\n  \
+           PRIMARY KEY (`id`)\n\
+         ) DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;"
+    );
+}
+
+#[test]
+fn test_schema_statements() {
+    let schema: Schema = [Group::descriptor(), User::descriptor()]
+        .into_iter()
+        .collect();
+    let statements = ddl::schema_statements(&schema, &Charset::default()).unwrap();
+
+    assert!(statements[0].starts_with("CREATE TABLE `groups`"));
+    assert!(statements[1].starts_with("CREATE TABLE `users`"));
+    assert!(statements[2].starts_with("CREATE TABLE `moderators`"));
+    assert_eq!(
+        statements[3],
+        "CREATE UNIQUE INDEX `idx_groups_name_unique` ON `groups` (`name`);"
+    );
+    assert_eq!(
+        statements[4],
+        "ALTER TABLE `users` ADD CONSTRAINT `users_group_id_fkey` FOREIGN KEY (`group_id`) \
+         REFERENCES `groups` (`id`) ON DELETE SET NULL;"
+    );
+    assert_eq!(statements.len(), 7);
+}
+
+#[test]
+fn test_unsupported() {
+    let error = |desc: &graphix::descriptor::EntityDescriptor| {
+        table_hcl(desc, &Charset::default())
+            .unwrap_err()
+            .to_string()
+    };
+
+    let mut desc = Document::descriptor().clone();
+    assert_eq!(
+        error(&desc),
+        "`documents`: column `tags` uses an array type (`text[]`), which MySQL doesn't support"
+    );
+
+    desc.fields.remove(1);
+    assert_eq!(
+        error(&desc),
+        "`documents`: column `published` uses a range type (`tstzrange`), which MySQL doesn't \
+         support"
+    );
+
+    desc.fields.remove(1);
+    assert_eq!(
+        error(&desc),
+        "`documents`: column `body` uses a full text search type (`tsvector`), which MySQL \
+         doesn't support"
+    );
+
+    desc.fields.remove(1);
+    assert_eq!(
+        error(&desc),
+        "`documents`: index `idx_documents_body` uses the `gin` index method, which MySQL \
+         doesn't support"
+    );
+}
+
+#[test]
+fn test_unsigned_columns() {
+    type Count = u64;
+
+    #[derive(Entity)]
+    #[graphix(table_name = "counters")]
+    #[allow(dead_code)]
+    struct Counter {
+        #[graphix(primary_key)]
+        id: core::primitive::u32,
+        small: u8,
+        medium: Option<u16>,
+        big: Count,
+        signed: i64,
+        #[graphix(sql_type = "bigint")]
+        explicit: u64,
+    }
+
+    let desc = Counter::descriptor();
+    let types = desc
+        .fields
+        .iter()
+        .map(|f| ColumnType::for_field(desc, f).unwrap().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        types,
+        [
+            "int unsigned",
+            "tinyint unsigned",
+            "smallint unsigned",
+            "bigint unsigned",
+            "bigint",
+            "bigint",
+        ]
+    );
+}
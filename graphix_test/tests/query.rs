@@ -0,0 +1,208 @@
+use graphix::query::Value;
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Status {
+    Active,
+    Banned,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "users")]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+    nickname: Option<String>,
+    age: i16,
+    status: Status,
+    created_at: time::OffsetDateTime,
+}
+
+const SELECT: &str = "SELECT \"id\", \"name\", \"nickname\", \"age\", \"status\", \"created_at\" \
+                      FROM \"public\".\"users\"";
+
+#[test]
+fn test_query() {
+    let (sql, params) = User::query()
+        .filter(UserColumns::name().eq("x"))
+        .order_by(UserColumns::created_at().desc())
+        .limit(10)
+        .to_sql();
+
+    assert_eq!(
+        sql,
+        format!(
+            "{} WHERE \"name\" = $1 ORDER BY \"created_at\" DESC LIMIT 10",
+            SELECT
+        )
+    );
+    assert_eq!(params, vec![Value::Text("x".to_string())]);
+}
+
+#[test]
+fn test_no_filter() {
+    let (sql, params) = User::query()
+        .order_by(UserColumns::age().asc())
+        .order_by(UserColumns::id().desc())
+        .offset(20)
+        .to_sql();
+
+    assert_eq!(
+        sql,
+        format!("{} ORDER BY \"age\" ASC, \"id\" DESC OFFSET 20", SELECT)
+    );
+    assert!(params.is_empty());
+}
+
+#[test]
+fn test_filters_are_anded() {
+    let (sql, params) = User::query()
+        .filter(UserColumns::age().gte(18))
+        .filter(UserColumns::age().lt(65))
+        .filter(UserColumns::nickname().is_null())
+        .to_sql();
+
+    assert_eq!(
+        sql,
+        format!(
+            "{} WHERE \"age\" >= $1 AND \"age\" < $2 AND \"nickname\" IS NULL",
+            SELECT
+        )
+    );
+    assert_eq!(params, vec![Value::SmallInt(18), Value::SmallInt(65)]);
+}
+
+#[test]
+fn test_combinators() {
+    let predicate = UserColumns::status()
+        .eq(Status::Active)
+        .or(UserColumns::name()
+            .ne("root")
+            .and(!UserColumns::nickname().is_not_null()));
+    let (sql, params) = User::query().filter(predicate).to_sql();
+
+    assert_eq!(
+        sql,
+        format!(
+            "{} WHERE \"status\" = $1 OR (\"name\" <> $2 AND NOT (\"nickname\" IS NOT NULL))",
+            SELECT
+        )
+    );
+    assert_eq!(
+        params,
+        vec![Value::Enum("active"), Value::Text("root".to_string())]
+    );
+}
+
+#[test]
+fn test_text() {
+    let mut params = Vec::new();
+    let sql = UserColumns::name()
+        .contains("50%")
+        .and(UserColumns::name().has_prefix("a_"))
+        .and(UserColumns::nickname().has_suffix("z"))
+        .and(UserColumns::nickname().contains_fold("Bob"))
+        .to_sql(&mut params);
+
+    assert_eq!(
+        sql,
+        "\"name\" LIKE $1 AND \"name\" LIKE $2 AND \"nickname\" LIKE $3 AND \"nickname\" ILIKE $4"
+    );
+    assert_eq!(
+        params,
+        ["%50\\%%", "a\\_%", "%z", "%Bob%"]
+            .map(|p| Value::Text(p.to_string()))
+            .to_vec()
+    );
+}
+
+#[test]
+fn test_in() {
+    let mut params = vec![Value::Null];
+    let sql = UserColumns::id()
+        .is_in([1, 2])
+        .and(UserColumns::status().not_in([Status::Banned]))
+        .to_sql(&mut params);
+
+    // numbered after the parameters already there
+    assert_eq!(sql, "\"id\" IN ($2, $3) AND \"status\" NOT IN ($4)");
+    assert_eq!(
+        params,
+        vec![
+            Value::Null,
+            Value::BigInt(1),
+            Value::BigInt(2),
+            Value::Enum("banned")
+        ]
+    );
+
+    let mut params = Vec::new();
+    let empty: [i64; 0] = [];
+    assert_eq!(UserColumns::id().is_in(empty).to_sql(&mut params), "FALSE");
+    assert_eq!(UserColumns::id().not_in(empty).to_sql(&mut params), "TRUE");
+    assert!(params.is_empty());
+}
+
+#[test]
+fn test_column_descriptor() {
+    let field = UserColumns::nickname().field();
+    assert_eq!(field.column_name, "nickname");
+    assert!(field.nullable);
+}
+
+#[test]
+fn test_keyword_entity_in_fn() {
+    // a `types` table generates a `Type` entity, which can't name a module
+    #[derive(Entity)]
+    #[graphix(table_name = "types")]
+    #[allow(dead_code)]
+    struct Type {
+        #[graphix(primary_key)]
+        id: i32,
+        kind: String,
+    }
+
+    let (sql, params) = Type::query().filter(TypeColumns::kind().eq("x")).to_sql();
+
+    assert_eq!(
+        sql,
+        "SELECT \"id\", \"kind\" FROM \"public\".\"types\" WHERE \"kind\" = $1"
+    );
+    assert_eq!(params, vec![Value::Text("x".to_string())]);
+}
+
+#[test]
+fn test_sql_type_predicates() {
+    // the declared column types decide, not the `String` fields
+    #[derive(Entity)]
+    #[graphix(table_name = "events")]
+    #[allow(dead_code)]
+    struct Event {
+        #[graphix(primary_key)]
+        id: i32,
+        #[graphix(sql_type = "date")]
+        day: String,
+        #[graphix(sql_type = "varchar(32)")]
+        title: String,
+    }
+
+    let (sql, params) = Event::query()
+        .filter(EventColumns::day().gte("2024-01-01"))
+        .filter(EventColumns::title().has_prefix("a"))
+        .to_sql();
+
+    assert_eq!(
+        sql,
+        "SELECT \"id\", \"day\", \"title\" FROM \"public\".\"events\" \
+         WHERE \"day\" >= $1 AND \"title\" LIKE $2"
+    );
+    assert_eq!(
+        params,
+        ["2024-01-01", "a%"]
+            .map(|p| Value::Text(p.to_string()))
+            .to_vec()
+    );
+}
@@ -0,0 +1,97 @@
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "users", edge(to = Team, m2m))]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    role: Role,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "teams")]
+#[allow(dead_code)]
+struct Team {
+    #[graphix(primary_key)]
+    id: i64,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "events", schema_name = "schema.audit")]
+#[allow(dead_code)]
+struct Event {
+    #[graphix(primary_key)]
+    id: i64,
+    role: Option<Role>,
+}
+
+#[test]
+fn test_registered() {
+    let schema = Schema::registered();
+
+    let tables: Vec<(&str, &str)> = schema
+        .tables()
+        .map(|t| (t.schema_name.as_str(), t.table_name.as_str()))
+        .collect();
+    assert_eq!(
+        tables,
+        vec![
+            ("schema.audit", "events"),
+            ("schema.public", "teams"),
+            ("schema.public", "users"),
+            ("schema.public", "users_teams"),
+        ]
+    );
+
+    assert_eq!(
+        schema.schemas().into_iter().collect::<Vec<_>>(),
+        vec!["audit", "public"]
+    );
+    assert_eq!(
+        schema.enums().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["role"]
+    );
+}
+
+#[test]
+fn test_add() {
+    let mut schema = Schema::new();
+    schema.add(Team::descriptor()).add(Team::descriptor());
+
+    assert_eq!(schema.tables().count(), 1);
+    assert_eq!(
+        schema.table("schema.public", "teams"),
+        Some(Team::descriptor())
+    );
+    assert!(schema.enums().next().is_none());
+}
+
+#[test]
+fn test_schema_atlas_hcl() {
+    let hcl = hcl::to_string(&Schema::registered().as_atlas_hcl()).unwrap();
+
+    assert!(hcl.starts_with(
+        "schema \"audit\" {}\n\nschema \"public\" {}\n\nenum \"role\" {\n  schema = schema.public\n"
+    ));
+
+    let position = |s: &str| hcl.find(s).unwrap();
+    assert!(position("table \"events\"") < position("table \"teams\""));
+    assert!(position("table \"teams\"") < position("table \"users\""));
+    assert!(position("table \"users\"") < position("table \"users_teams\""));
+
+    // the same entities always render the same document
+    assert_eq!(
+        hcl,
+        hcl::to_string(&Schema::registered().as_atlas_hcl()).unwrap()
+    );
+}
@@ -0,0 +1,85 @@
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::{EdgeSchema, Entity, SqlEnum};
+use std::collections::HashSet;
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Visibility {
+    Public,
+    Private,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "tags")]
+#[allow(dead_code)]
+struct Tag {
+    #[graphix(primary_key, sql_type = "varchar(64)")]
+    name: String,
+}
+
+#[derive(EdgeSchema)]
+#[allow(dead_code)]
+struct Tagging {
+    #[graphix(default_expr = "now()")]
+    tagged_at: time::OffsetDateTime,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "articles",
+    edge(to = Tag, m2m, edge_schema = Tagging),
+    index(columns = ["keywords"], method = "gin")
+)]
+#[allow(dead_code)]
+struct Article {
+    #[graphix(primary_key)]
+    id: uuid::Uuid,
+    #[graphix(unique)]
+    slug: String,
+    #[graphix(default = "public")]
+    visibility: Visibility,
+    keywords: Vec<String>,
+    score: Option<f64>,
+}
+
+const SNAPSHOT: &str = "tests/snapshots/schema.json";
+
+/// Fails when the schema changes, rerun with `GRAPHIX_UPDATE_SNAPSHOTS=1` to accept the change.
+#[test]
+fn test_snapshot() {
+    let snapshot = Schema::registered().to_snapshot();
+
+    if std::env::var("GRAPHIX_UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+        std::fs::write(SNAPSHOT, &snapshot).unwrap();
+    }
+    assert_eq!(snapshot, std::fs::read_to_string(SNAPSHOT).unwrap());
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let schema = Schema::registered();
+    let loaded = Schema::from_snapshot(&schema.to_snapshot()).unwrap();
+
+    assert_eq!(loaded, schema);
+    assert_eq!(loaded.to_snapshot(), schema.to_snapshot());
+}
+
+#[test]
+fn test_snapshot_version() {
+    let err = Schema::from_snapshot(r#"{"version": 99, "enums": [], "tables": []}"#).unwrap_err();
+    assert!(err.to_string().contains("unsupported snapshot version 99"));
+}
+
+#[test]
+fn test_descriptors_hash() {
+    let set: HashSet<_> = [
+        Article::descriptor(),
+        Article::descriptor(),
+        Tag::descriptor(),
+    ]
+    .into_iter()
+    .cloned()
+    .collect();
+    assert_eq!(set.len(), 2);
+}
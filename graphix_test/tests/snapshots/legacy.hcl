@@ -0,0 +1,135 @@
+schema "public" {
+}
+schema "billing" {
+}
+enum "order_status" {
+  schema = schema.billing
+  values = ["pending", "shipped", "in-transit"]
+}
+table "customers" {
+  schema = schema.public
+  column "id" {
+    null = false
+    type = bigserial
+  }
+  column "email" {
+    null = false
+    type = character_varying(255)
+  }
+  column "type" {
+    null = true
+    type = text
+  }
+  column "credit" {
+    null    = false
+    type    = numeric(10,2)
+    default = 0
+  }
+  column "created_at" {
+    null    = false
+    type    = timestamptz
+    default = sql("now()")
+  }
+  primary_key {
+    columns = [column.id]
+  }
+  index "idx_customers_email_unique" {
+    unique  = true
+    columns = [column.email]
+  }
+  index "customers_lower_email" {
+    on {
+      expr = "lower((email)::text)"
+    }
+  }
+}
+table "orders" {
+  schema = schema.billing
+  column "id" {
+    null    = false
+    type    = uuid
+    default = sql("gen_random_uuid()")
+  }
+  column "customer_id" {
+    null = false
+    type = bigint
+  }
+  column "status" {
+    null    = false
+    type    = enum.order_status
+    default = "pending"
+  }
+  column "tags" {
+    null = false
+    type = sql("text[]")
+  }
+  column "discount" {
+    null    = true
+    type    = double_precision
+    default = -0.5
+  }
+  primary_key {
+    columns = [column.id]
+  }
+  foreign_key "orders_customer_id_fkey" {
+    columns     = [column.customer_id]
+    ref_columns = [table.public.customers.column.id]
+    on_update   = NO_ACTION
+    on_delete   = CASCADE
+  }
+  index "idx_orders_customer_id" {
+    columns = [column.customer_id]
+  }
+  index "idx_orders_tags" {
+    type    = GIN
+    columns = [column.tags]
+  }
+}
+table "shipments" {
+  schema = schema.billing
+  column "order_id" {
+    null = false
+    type = uuid
+  }
+  column "carrier" {
+    null = false
+    type = character_varying(32)
+  }
+  column "shipped_at" {
+    null = true
+    type = timestamp(3)
+  }
+  primary_key {
+    columns = [column.order_id, column.carrier]
+  }
+  foreign_key "shipments_order_id_fkey" {
+    columns     = [column.order_id]
+    ref_columns = [table.orders.column.id]
+  }
+  index "idx_shipments_order_id_unique" {
+    unique  = true
+    columns = [column.order_id]
+  }
+}
+table "audit_log" {
+  schema = schema.public
+  column "payload" {
+    null = true
+    type = jsonb
+  }
+  column "at" {
+    null = false
+    type = sql("timestamp with time zone")
+  }
+  index "audit_log_recent" {
+    columns = [column.at]
+    where   = "at > '2024-01-01'"
+  }
+}
+table "legacy_view_source" {
+  schema = schema.public
+  column "data" {
+    null = false
+    type = xml
+  }
+}
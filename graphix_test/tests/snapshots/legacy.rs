@@ -0,0 +1,76 @@
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum)]
+#[graphix(schema_name = "schema.billing")]
+pub enum OrderStatus {
+    Pending,
+    Shipped,
+    #[graphix(rename = "in-transit")]
+    InTransit,
+}
+
+#[derive(Entity)]
+#[graphix(
+    schema_name = "schema.billing",
+    index(columns = ["customer_id"]),
+    index(columns = ["tags"], method = "gin"),
+    edge(to = Customer, field = "customer_id", on_delete = "cascade", on_update = "no_action"),
+)]
+pub struct Order {
+    #[graphix(primary_key, default_expr = "gen_random_uuid()")]
+    pub id: uuid::Uuid,
+    pub customer_id: i64,
+    #[graphix(default = "pending")]
+    pub status: OrderStatus,
+    pub tags: Vec<String>,
+    #[graphix(default = -0.5)]
+    pub discount: Option<f64>,
+}
+
+#[derive(Entity)]
+#[graphix(
+    schema_name = "schema.billing",
+    primary_key = ["order_id", "carrier"],
+    edge(to = Order, field = "order_id", unique),
+)]
+pub struct Shipment {
+    #[graphix(unique)]
+    pub order_id: uuid::Uuid,
+    #[graphix(sql_type = "varchar(32)")]
+    pub carrier: String,
+    #[graphix(sql_type = "timestamp(3)")]
+    pub shipped_at: Option<time::PrimitiveDateTime>,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "audit_log",
+    no_primary_key,
+    index(columns = ["at"], predicate = "at > '2024-01-01'", name = "audit_log_recent"),
+)]
+pub struct AuditLog {
+    pub payload: Option<serde_json::Value>,
+    pub at: time::OffsetDateTime,
+}
+
+#[derive(Entity)]
+#[graphix(index(parts = [expr("lower((email)::text)")], name = "customers_lower_email"))]
+pub struct Customer {
+    #[graphix(primary_key, sql_type = "bigserial")]
+    pub id: i64,
+    #[graphix(sql_type = "varchar(255)", unique)]
+    pub email: String,
+    #[graphix(colname = "type")]
+    pub type_: Option<String>,
+    #[graphix(sql_type = "numeric(10,2)", default = 0)]
+    pub credit: rust_decimal::Decimal,
+    #[graphix(default_expr = "now()")]
+    pub created_at: time::OffsetDateTime,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "legacy_view_source", no_primary_key)]
+pub struct LegacyViewSource {
+    #[graphix(sql_type = "xml")]
+    pub data: String,
+}
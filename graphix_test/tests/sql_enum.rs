@@ -0,0 +1,129 @@
+use graphix::entity::Entity;
+use graphix::sql::postgres::{SqlEnum, SqlType};
+use graphix::{Entity, SqlEnum};
+
+#[derive(SqlEnum, Debug, PartialEq)]
+#[allow(dead_code)]
+enum OrderStatus {
+    Pending,
+    InProgress,
+    #[graphix(rename = "done")]
+    Completed,
+}
+
+#[derive(SqlEnum)]
+#[graphix(name = "user_role", schema_name = "schema.auth")]
+#[allow(dead_code)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "orders")]
+#[allow(dead_code)]
+struct Order {
+    #[graphix(primary_key)]
+    id: i64,
+    status: OrderStatus,
+    previous_status: Option<OrderStatus>,
+    roles: Vec<Role>,
+}
+
+#[test]
+fn test_labels() {
+    assert_eq!(OrderStatus::VALUES, &["pending", "in_progress", "done"]);
+    assert_eq!(OrderStatus::InProgress.as_label(), "in_progress");
+    assert_eq!(
+        OrderStatus::from_label("done"),
+        Some(OrderStatus::Completed)
+    );
+    assert_eq!(OrderStatus::from_label("completed"), None);
+}
+
+#[test]
+fn test_enum_descriptor() {
+    let desc = Role::enum_descriptor().unwrap();
+    assert_eq!(desc.name, "user_role");
+    assert_eq!(desc.schema_name, "schema.auth");
+    assert_eq!(desc.values, vec!["admin", "member"]);
+}
+
+#[test]
+fn test_enum_fields() {
+    let order = Order {
+        id: 1,
+        status: OrderStatus::Pending,
+        previous_status: None,
+        roles: vec![],
+    };
+    let desc = order.entity_descriptor();
+    let types: Vec<String> = desc.fields.iter().map(|f| f.sql_type.to_string()).collect();
+    assert_eq!(
+        types,
+        vec![
+            "bigint",
+            "enum.order_status",
+            "enum.order_status",
+            "enum.user_role[]"
+        ]
+    );
+
+    let names: Vec<&str> = desc.enums.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["order_status", "user_role"]);
+}
+
+#[test]
+fn test_enum_atlas_hcl() {
+    let order = Order {
+        id: 1,
+        status: OrderStatus::Pending,
+        previous_status: None,
+        roles: vec![],
+    };
+    let hcl = hcl::to_string(&order.as_atlas_body()).unwrap();
+
+    assert!(hcl.contains(
+        "enum \"order_status\" {\n  schema = schema.public\n  values = [\n    \"pending\",\n    \"in_progress\",\n    \"done\"\n  ]\n}"
+    ));
+    assert!(hcl.contains("enum \"user_role\" {\n  schema = schema.auth"));
+    assert!(hcl.contains("type = enum.order_status"));
+    assert!(hcl.find("enum \"user_role\"").unwrap() < hcl.find("table \"orders\"").unwrap());
+}
+
+#[test]
+fn test_enum_array_atlas_hcl() {
+    let order = Order {
+        id: 1,
+        status: OrderStatus::Pending,
+        previous_status: None,
+        roles: vec![],
+    };
+    let hcl = hcl::to_string(&order.as_atlas_hcl()).unwrap();
+
+    // arrays are spelled in SQL, naming the enum in the schema it's declared in
+    assert!(hcl.contains("type = sql(\"\\\"auth\\\".\\\"user_role\\\"[]\")"));
+}
+
+#[derive(Entity)]
+#[allow(dead_code)]
+struct Shipment {
+    #[graphix(primary_key)]
+    id: i64,
+    #[graphix(default = "pending")]
+    status: OrderStatus,
+}
+
+#[test]
+fn test_enum_default() {
+    use graphix::descriptor::ColumnDefault;
+
+    let s = Shipment {
+        id: 1,
+        status: OrderStatus::Pending,
+    };
+    assert_eq!(
+        s.entity_descriptor().fields[1].default,
+        Some(ColumnDefault::Str("pending".to_string()))
+    );
+}
@@ -0,0 +1,169 @@
+use graphix::entity::Entity;
+use graphix::schema::Schema;
+use graphix::sql::sqlite::ddl;
+use graphix::{Entity, SqlEnum};
+use rusqlite::Connection;
+
+#[derive(SqlEnum)]
+#[allow(dead_code)]
+enum Status {
+    Active,
+    Banned,
+}
+
+#[derive(Entity)]
+#[graphix(table_name = "groups", edge(from = User, field = "group_id"))]
+#[allow(dead_code)]
+struct Group {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+}
+
+#[derive(Entity)]
+#[graphix(
+    table_name = "users",
+    edge(to = Group, field = "group_id", on_delete = "set_null"),
+    edge(to = Group, m2m, through = "moderators"),
+    index(parts = [expr("lower(name)")], name = "idx_users_name")
+)]
+#[allow(dead_code)]
+struct User {
+    #[graphix(primary_key)]
+    id: i64,
+    name: String,
+    #[graphix(unique)]
+    email: String,
+    #[graphix(default = "active")]
+    status: Status,
+    #[graphix(default = true)]
+    verified: bool,
+    #[graphix(default_expr = "now()")]
+    created_at: time::OffsetDateTime,
+    group_id: Option<i64>,
+}
+
+/// An in-memory database with the schema of `Group` and `User`.
+fn connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+
+    let schema: Schema = [Group::descriptor(), User::descriptor()]
+        .into_iter()
+        .collect();
+    conn.execute_batch(&ddl::schema_statements(&schema).join("\n"))
+        .unwrap();
+    conn
+}
+
+#[test]
+fn test_create_table() {
+    assert_eq!(
+        ddl::create_table(User::descriptor()),
+        "CREATE TABLE \"users\" (\n  \
+           \"id\" INTEGER NOT NULL,\n  \
+           \"name\" TEXT NOT NULL,\n  \
+           \"email\" TEXT NOT NULL,\n  \
+           \"status\" TEXT NOT NULL DEFAULT 'active' CHECK (\"status\" IN ('active', 'banned')),\n  \
+           \"verified\" INTEGER NOT NULL DEFAULT 1,\n  \
+           \"created_at\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,\n  \
+           \"group_id\" INTEGER,\n  \
+           PRIMARY KEY (\"id\"),\n  \
+           FOREIGN KEY (\"group_id\") REFERENCES \"groups\" (\"id\") ON DELETE SET NULL\n\
+         );"
+    );
+}
+
+#[test]
+fn test_statement_order() {
+    let statements = ddl::entity_statements(User::descriptor());
+
+    assert!(statements[0].starts_with("CREATE TABLE \"users\""));
+    assert!(statements[1].starts_with("CREATE TABLE \"moderators\""));
+    assert_eq!(
+        statements[2],
+        "CREATE UNIQUE INDEX \"idx_users_email_unique\" ON \"users\" (\"email\");"
+    );
+    assert_eq!(
+        statements[3],
+        "CREATE INDEX \"idx_users_name\" ON \"users\" ((lower(name)));"
+    );
+}
+
+#[test]
+fn test_defaults() {
+    let conn = connection();
+    conn.execute("INSERT INTO groups (id, name) VALUES (1, 'admins')", [])
+        .unwrap();
+    conn.execute(
+        "INSERT INTO users (id, name, email, group_id) VALUES (1, 'ann', 'ann@example.com', 1)",
+        [],
+    )
+    .unwrap();
+
+    let (status, verified, created_at): (String, bool, String) = conn
+        .query_row(
+            "SELECT status, verified, created_at FROM users WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(status, "active");
+    assert!(verified);
+    assert!(!created_at.is_empty());
+}
+
+#[test]
+fn test_constraints() {
+    let conn = connection();
+    conn.execute("INSERT INTO groups (id, name) VALUES (1, 'admins')", [])
+        .unwrap();
+    conn.execute(
+        "INSERT INTO users (id, name, email, group_id) VALUES (1, 'ann', 'ann@example.com', 1)",
+        [],
+    )
+    .unwrap();
+
+    let insert = |sql: &str| conn.execute(sql, []).unwrap_err().to_string();
+
+    // unique email
+    assert!(
+        insert("INSERT INTO users (id, name, email) VALUES (2, 'bob', 'ann@example.com')")
+            .contains("UNIQUE constraint failed: users.email")
+    );
+    // enum values
+    assert!(insert(
+        "INSERT INTO users (id, name, email, status) VALUES (2, 'bob', 'bob@example.com', 'gone')"
+    )
+    .contains("CHECK constraint failed"));
+    // missing group
+    assert!(insert(
+        "INSERT INTO users (id, name, email, group_id) VALUES (2, 'bob', 'bob@example.com', 9)"
+    )
+    .contains("FOREIGN KEY constraint failed"));
+}
+
+#[test]
+fn test_on_delete() {
+    let conn = connection();
+    conn.execute_batch(
+        "INSERT INTO groups (id, name) VALUES (1, 'admins');
+         INSERT INTO users (id, name, email, group_id) VALUES (1, 'ann', 'ann@example.com', 1);
+         INSERT INTO moderators (user_id, group_id) VALUES (1, 1);
+         DELETE FROM groups WHERE id = 1;",
+    )
+    .unwrap();
+
+    // the foreign key on users is set to null, the join table rows cascade
+    let group_id: Option<i64> = conn
+        .query_row("SELECT group_id FROM users WHERE id = 1", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(group_id, None);
+
+    let moderators: i64 = conn
+        .query_row("SELECT count(*) FROM moderators", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(moderators, 0);
+}
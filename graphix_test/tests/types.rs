@@ -0,0 +1,86 @@
+use graphix::entity::Entity;
+use graphix::Entity;
+use std::net::IpAddr;
+
+/// Derives a single column entity for `$typ` and returns its field descriptor.
+macro_rules! descriptor_for {
+    ($typ:ty, $value:expr) => {{
+        #[derive(Entity)]
+        #[graphix(no_primary_key)]
+        #[allow(dead_code)]
+        struct Single {
+            value: $typ,
+        }
+
+        let mut desc = Single { value: $value }.entity_descriptor();
+        desc.fields.remove(0)
+    }};
+}
+
+#[test]
+fn test_uuid() {
+    let field = descriptor_for!(uuid::Uuid, uuid::Uuid::nil());
+    assert_eq!(field.sql_type.to_string(), "uuid");
+    assert!(!field.nullable);
+}
+
+#[test]
+fn test_time_offset_date_time() {
+    let field = descriptor_for!(time::OffsetDateTime, time::OffsetDateTime::UNIX_EPOCH);
+    assert_eq!(field.sql_type.to_string(), "timestamptz");
+}
+
+#[test]
+fn test_time_date() {
+    let field = descriptor_for!(time::Date, time::Date::MIN);
+    assert_eq!(field.sql_type.to_string(), "date");
+}
+
+#[test]
+fn test_time_time() {
+    let field = descriptor_for!(time::Time, time::Time::MIDNIGHT);
+    assert_eq!(field.sql_type.to_string(), "time");
+}
+
+#[test]
+fn test_chrono_date_time_utc() {
+    let field = descriptor_for!(
+        chrono::DateTime<chrono::Utc>,
+        chrono::DateTime::<chrono::Utc>::UNIX_EPOCH
+    );
+    assert_eq!(field.sql_type.to_string(), "timestamptz");
+}
+
+#[test]
+fn test_serde_json_value() {
+    let field = descriptor_for!(serde_json::Value, serde_json::Value::Null);
+    assert_eq!(field.sql_type.to_string(), "jsonb");
+}
+
+#[test]
+fn test_rust_decimal() {
+    let field = descriptor_for!(rust_decimal::Decimal, rust_decimal::Decimal::ZERO);
+    assert_eq!(field.sql_type.to_string(), "numeric");
+}
+
+#[test]
+fn test_ip_addr() {
+    let field = descriptor_for!(IpAddr, IpAddr::from([127, 0, 0, 1]));
+    assert_eq!(field.sql_type.to_string(), "inet");
+}
+
+#[test]
+fn test_ipnetwork() {
+    let field = descriptor_for!(
+        ipnetwork::IpNetwork,
+        ipnetwork::IpNetwork::from(IpAddr::from([10, 0, 0, 0]))
+    );
+    assert_eq!(field.sql_type.to_string(), "cidr");
+}
+
+#[test]
+fn test_optional_external_type() {
+    let field = descriptor_for!(Option<uuid::Uuid>, None);
+    assert_eq!(field.sql_type.to_string(), "uuid");
+    assert!(field.nullable);
+}